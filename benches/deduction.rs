@@ -0,0 +1,120 @@
+//! Deduction scaling — one forward-chaining pass over synthetic taxonomies
+//! Run with `cargo bench --bench deduction`. Each taxonomy is a complete tree
+//! with branching factor 10; depth 3, 4 and 5 give ~1k, ~10k and ~100k
+//! InheritanceLinks. The pair scan is the pre-index algorithm, kept as a
+//! reference for the sizes where it finishes in reasonable time. The last
+//! column closes the taxonomy, then repeatedly adds 10 leaves and chains
+//! them incrementally.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use coggy::atom::{AtomId, AtomType, TruthValue};
+use coggy::atomspace::AtomSpace;
+use coggy::pln;
+use coggy::rules::Deduction;
+
+const BRANCHING: usize = 10;
+
+fn taxonomy(depth: u32) -> AtomSpace {
+    let mut space = AtomSpace::new();
+    let tv = TruthValue::new(0.95, 0.9);
+    let (root, _) = space.add_node(AtomType::ConceptNode, "n", tv);
+    let mut level = vec![(root, "n".to_string())];
+    for _ in 0..depth {
+        let mut next = Vec::with_capacity(level.len() * BRANCHING);
+        for (parent, name) in &level {
+            for i in 0..BRANCHING {
+                let child_name = format!("{}.{}", name, i);
+                let (child, _) = space.add_node(AtomType::ConceptNode, &child_name, tv);
+                space.add_link(AtomType::InheritanceLink, vec![child, *parent], tv);
+                next.push((child, child_name));
+            }
+        }
+        level = next;
+    }
+    space
+}
+
+/// Conclusions the old quadratic scan would propose: every ordered pair of
+/// links A→B, B→C without an existing A→C
+fn pair_scan(space: &AtomSpace) -> usize {
+    let links: Vec<(AtomId, AtomId)> = space
+        .get_by_type(AtomType::InheritanceLink)
+        .into_iter()
+        .filter_map(|id| {
+            let o = &space.get(id)?.outgoing;
+            Some((o[0], o[1]))
+        })
+        .collect();
+    let mut proposed = HashSet::new();
+    for &(a, b1) in &links {
+        for &(b2, c) in &links {
+            if b1 == b2
+                && a != c
+                && space
+                    .find_link(AtomType::InheritanceLink, &[a, c])
+                    .is_none()
+            {
+                proposed.insert((a, c));
+            }
+        }
+    }
+    proposed.len()
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let out = f();
+    (out, start.elapsed())
+}
+
+fn main() {
+    println!(
+        "{:>7} {:>12} {:>14} {:>14} {:>14}",
+        "links", "inferences", "index join", "pair scan", "+10 leaves"
+    );
+    for depth in 3..=5 {
+        let base = taxonomy(depth);
+        let links = base.get_by_type(AtomType::InheritanceLink).len();
+
+        let scan = (links <= 20_000).then(|| time(|| pair_scan(&base)));
+
+        let mut space = base;
+        let (inferences, joined) = time(|| pln::forward_chain(&mut space, 1).len());
+        if let Some((proposed, _)) = scan {
+            assert_eq!(proposed, inferences, "join and scan disagree");
+        }
+        // Close the taxonomy so the incremental pass only sees the new leaves
+        pln::forward_chain_incremental(&mut space, u32::MAX, &[&Deduction]);
+
+        // Median of several rounds: a round that happens to grow the space's
+        // hash maps pays for rehashing every atom
+        let tv = TruthValue::new(0.95, 0.9);
+        let parent = space.find_node(AtomType::ConceptNode, "n.0.0").unwrap();
+        let mut rounds: Vec<Duration> = (0..9)
+            .map(|round| {
+                for i in 0..10 {
+                    let name = format!("new.{}.{}", round, i);
+                    let (leaf, _) = space.add_node(AtomType::ConceptNode, &name, tv);
+                    space.add_link(AtomType::InheritanceLink, vec![leaf, parent], tv);
+                }
+                time(|| pln::forward_chain_incremental(&mut space, 5, &[&Deduction])).1
+            })
+            .collect();
+        rounds.sort();
+        let delta = rounds[rounds.len() / 2];
+
+        println!(
+            "{:>7} {:>12} {:>12.1}ms {:>14} {:>12.2}ms",
+            links,
+            inferences,
+            joined.as_secs_f64() * 1e3,
+            scan.map_or("skipped".to_string(), |(_, d)| format!(
+                "{:.1}ms",
+                d.as_secs_f64() * 1e3
+            )),
+            delta.as_secs_f64() * 1e3,
+        );
+    }
+}
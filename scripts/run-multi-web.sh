@@ -3,7 +3,8 @@ set -euo pipefail
 
 PORT_LIST="${PORT_LIST:-8421 8431 8451}"
 LOG_DIR="$(cd "$(dirname "$0")/.." && pwd)/logs"
-mkdir -p "$LOG_DIR"
+SNAPSHOT_DIR="${SNAPSHOT_DIR:-$(cd "$(dirname "$0")/.." && pwd)/data}"
+mkdir -p "$LOG_DIR" "$SNAPSHOT_DIR"
 
 for port in $PORT_LIST; do
   LOG_FILE="$LOG_DIR/web-$port.log"
@@ -15,7 +16,7 @@ for port in $PORT_LIST; do
   echo "[$(date --iso-8601=seconds)] Launching Coggy web on port $port" | tee -a "$LOG_FILE"
   (
     cd "$(dirname "$0")/.."
-    COGGY_PORT="$port" nohup ./scripts/run-web.sh >> "$LOG_FILE" 2>&1 &
+    COGGY_PORT="$port" COGGY_SNAPSHOT="$SNAPSHOT_DIR/coggy-$port.json" nohup ./scripts/run-web.sh >> "$LOG_FILE" 2>&1 &
   )
 done
 
@@ -0,0 +1,387 @@
+//! Question answering — reads answers out of the AtomSpace
+//! Answers never add atoms: they are assembled from asserted and inferred
+//! links already in the space, ranked by truth value and attention.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::atom::*;
+use crate::atomspace::AtomSpace;
+use crate::backward::{self, BackwardConfig, Proof};
+use crate::parse::Question;
+use crate::pattern::{self, Bindings, Pattern, Term};
+use crate::rules::{InferenceRule, ParentSimilarity};
+
+/// Weight of relative STI in the ranking score; truth (s × c) dominates
+const STI_WEIGHT: f64 = 0.25;
+/// Scored resemblances weaker than this are noise from broad shared
+/// parents, not worth an answer
+const MIN_RESEMBLANCE: f64 = 0.1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnswerFact {
+    /// Stored atom behind the fact; None when it was only proved
+    pub atom: Option<AtomId>,
+    pub text: String,
+    pub tv: TruthValue,
+    pub sti: f64,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Answer {
+    pub question: String,
+    /// The atom the question is about, if the space knows it
+    pub subject: Option<AtomId>,
+    /// Best first
+    pub facts: Vec<AnswerFact>,
+    /// Supporting proof for yes/no questions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Proof>,
+}
+
+pub fn answer(space: &AtomSpace, question: &Question) -> Answer {
+    answer_with(space, question, &BackwardConfig::default())
+}
+
+/// `answer` with explicit backward-chaining limits for yes/no questions
+pub fn answer_with(space: &AtomSpace, question: &Question, config: &BackwardConfig) -> Answer {
+    match question {
+        Question::WhatIs(subject) => what_is(space, subject),
+        Question::IsA(subject, parent) => is_a(space, subject, parent, config),
+        Question::Resembles(subject) => resembles(space, subject),
+    }
+}
+
+/// Prove subject→parent by backward chaining; nothing is materialized
+fn is_a(space: &AtomSpace, subject: &str, parent: &str, config: &BackwardConfig) -> Answer {
+    let question = format!("is {} a {}", subject, parent);
+    let sid = space.find_node(AtomType::ConceptNode, subject);
+    let proof = sid
+        .zip(space.find_node(AtomType::ConceptNode, parent))
+        .and_then(|(s, p)| backward::prove(space, s, p, config));
+    let facts = proof
+        .iter()
+        .map(|p| AnswerFact {
+            atom: p.link,
+            text: isa_text(subject, parent, p.tv),
+            tv: p.tv,
+            sti: p
+                .link
+                .and_then(|id| space.get(id))
+                .map_or(0.0, |a| a.av.sti),
+            score: p.score(),
+        })
+        .collect();
+    Answer {
+        question,
+        subject: sid,
+        facts,
+        proof,
+    }
+}
+
+fn what_is(space: &AtomSpace, subject: &str) -> Answer {
+    let question = format!("what is {}", subject);
+    let Some(sid) = space.find_node(AtomType::ConceptNode, subject) else {
+        return Answer {
+            question,
+            subject: None,
+            facts: Vec::new(),
+            proof: None,
+        };
+    };
+
+    let mut facts = Vec::new();
+
+    // Ancestors: asserted and PLN-inferred inheritance links
+    let ancestors = Pattern::new(vec![Term::inheritance(Term::Atom(sid), Term::var("X"))]);
+    for m in pattern::find_matches(space, &ancestors) {
+        let link = m.clause_atoms[0];
+        let tv = space.get(link).expect("matched atom exists").tv;
+        let text = isa_text(subject, &space.short_name(m.bindings["X"]), tv);
+        facts.push(fact(space, link, text));
+    }
+
+    // Concepts the subject is an instance of, kept apart from its ancestors
+    let member = Pattern::new(vec![Term::link(
+        AtomType::MemberLink,
+        vec![Term::Atom(sid), Term::var("X")],
+    )]);
+    for m in pattern::find_matches(space, &member) {
+        let text = format!(
+            "{} member-of {}",
+            subject,
+            space.short_name(m.bindings["X"])
+        );
+        facts.push(fact(space, m.clause_atoms[0], text));
+    }
+
+    // Evaluation facts with the subject in either argument position
+    for (args, subject_first) in [
+        (vec![Term::Atom(sid), Term::var("Y")], true),
+        (vec![Term::var("Y"), Term::Atom(sid)], false),
+    ] {
+        let eval = Pattern::new(vec![Term::link(
+            AtomType::EvaluationLink,
+            vec![Term::var("P"), Term::link(AtomType::ListLink, args)],
+        )]);
+        for m in pattern::find_matches(space, &eval) {
+            let pred = space.short_name(m.bindings["P"]);
+            let other = space.short_name(m.bindings["Y"]);
+            let text = if subject_first {
+                format!("{} {} {}", subject, pred, other)
+            } else {
+                format!("{} {} {}", other, pred, subject)
+            };
+            facts.push(fact(space, m.clause_atoms[0], text));
+        }
+    }
+
+    rank(&mut facts);
+    Answer {
+        question,
+        subject: Some(sid),
+        facts,
+        proof: None,
+    }
+}
+
+/// Stored similarities of the subject, then terms sharing a parent with it,
+/// scored by the parent-similarity rule without adding the links
+fn resembles(space: &AtomSpace, subject: &str) -> Answer {
+    let question = format!("what resembles {}", subject);
+    let Some(sid) = space.find_node(AtomType::ConceptNode, subject) else {
+        return Answer {
+            question,
+            subject: None,
+            facts: Vec::new(),
+            proof: None,
+        };
+    };
+
+    let mut facts = Vec::new();
+    let mut known = HashSet::from([sid]);
+    let stored = Pattern::new(vec![Term::similarity(Term::Atom(sid), Term::var("X"))]);
+    for m in pattern::find_matches(space, &stored) {
+        let other = m.bindings["X"];
+        known.insert(other);
+        let text = format!("{} resembles {}", subject, space.short_name(other));
+        facts.push(fact(space, m.clause_atoms[0], text));
+    }
+
+    // Best shared parent per term not already known to be similar
+    let rule = ParentSimilarity;
+    let mut derived: BTreeMap<AtomId, TruthValue> = BTreeMap::new();
+    let bindings = Bindings::from([("A".to_string(), sid)]);
+    for m in pattern::find_matches_from(space, &rule.premises(), &bindings) {
+        let other = m.bindings["B"];
+        if known.contains(&other) || !rule.admits(space, &m) {
+            continue;
+        }
+        let tv = rule.truth_value(space, &m);
+        let best = derived.entry(other).or_insert(tv);
+        if tv.strength * tv.confidence > best.strength * best.confidence {
+            *best = tv;
+        }
+    }
+    derived.retain(|_, tv| tv.strength >= MIN_RESEMBLANCE);
+    facts.extend(derived.into_iter().map(|(other, tv)| AnswerFact {
+        atom: None,
+        text: format!("{} resembles {}", subject, space.short_name(other)),
+        tv,
+        sti: 0.0,
+        score: 0.0,
+    }));
+
+    rank(&mut facts);
+    Answer {
+        question,
+        subject: Some(sid),
+        facts,
+        proof: None,
+    }
+}
+
+/// "X is-a Y", or "X is-not-a Y" when the link says it is mostly false
+fn isa_text(subject: &str, parent: &str, tv: TruthValue) -> String {
+    if tv.strength < 0.5 {
+        format!("{} is-not-a {}", subject, parent)
+    } else {
+        format!("{} is-a {}", subject, parent)
+    }
+}
+
+fn fact(space: &AtomSpace, atom: AtomId, text: String) -> AnswerFact {
+    let a = space.get(atom).expect("matched atom exists");
+    AnswerFact {
+        atom: Some(atom),
+        text,
+        tv: a.tv,
+        sti: a.av.sti,
+        score: 0.0,
+    }
+}
+
+/// Score = s × c + STI_WEIGHT × (sti / max sti), highest first
+fn rank(facts: &mut [AnswerFact]) {
+    let max_sti = facts.iter().map(|f| f.sti).fold(0.0, f64::max);
+    for f in facts.iter_mut() {
+        let attention = if max_sti > 0.0 {
+            f.sti.max(0.0) / max_sti
+        } else {
+            0.0
+        };
+        f.score = f.tv.strength * f.tv.confidence + STI_WEIGHT * attention;
+    }
+    facts.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontology;
+    use crate::parse;
+    use crate::pln;
+
+    fn space() -> AtomSpace {
+        let mut s = AtomSpace::new();
+        ontology::load_base_ontology(&mut s);
+        s
+    }
+
+    #[test]
+    fn what_is_lists_ancestors_and_evaluations() {
+        let mut s = space();
+        parse::parse_input(&mut s, "cat likes fish");
+        pln::forward_chain(&mut s, 2);
+        let a = answer(&s, &Question::WhatIs("cat".into()));
+        assert!(a.subject.is_some());
+        let texts: Vec<_> = a.facts.iter().map(|f| f.text.as_str()).collect();
+        assert!(texts.contains(&"cat is-a mammal"));
+        assert!(texts.contains(&"cat is-a animal"), "inferred links count");
+        assert!(texts.contains(&"cat likes fish"));
+
+        parse::parse_input(&mut s, "tom is an instance of cat");
+        let a = answer(&s, &Question::WhatIs("tom".into()));
+        let texts: Vec<_> = a.facts.iter().map(|f| f.text.as_str()).collect();
+        assert_eq!(texts, vec!["tom member-of cat"]);
+    }
+
+    #[test]
+    fn facts_ranked_by_truth_then_attention() {
+        let mut s = space();
+        pln::forward_chain(&mut s, 2);
+        let a = answer(&s, &Question::WhatIs("cat".into()));
+        // Direct ontology link is more confident than any deduced one
+        assert_eq!(a.facts[0].text, "cat is-a mammal");
+        assert!(a.facts.windows(2).all(|w| w[0].score >= w[1].score));
+
+        // Attention breaks ties between equally confident facts
+        let dog = s.find_node(AtomType::ConceptNode, "dog").unwrap();
+        let mammal = s.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let animal = s.find_node(AtomType::ConceptNode, "animal").unwrap();
+        let dm = s
+            .find_link(AtomType::InheritanceLink, &[dog, mammal])
+            .unwrap();
+        let da = s
+            .find_link(AtomType::InheritanceLink, &[dog, animal])
+            .unwrap();
+        s.set_tv(da, s.get(dm).unwrap().tv);
+        s.set_sti(da, 20.0);
+        let a = answer(&s, &Question::WhatIs("dog".into()));
+        assert_eq!(a.facts[0].text, "dog is-a animal");
+    }
+
+    #[test]
+    fn is_a_answered_by_proof() {
+        let s = space();
+        let size = s.size();
+        let q = Question::IsA("cat".into(), "living-thing".into());
+        let a = answer(&s, &q);
+        let proof = a.proof.expect("provable");
+        assert_eq!(proof.leaves(), 3);
+        assert_eq!(a.facts.len(), 1);
+        assert_eq!(a.facts[0].text, "cat is-a living-thing");
+        assert_eq!(s.size(), size);
+
+        let no = answer(&s, &Question::IsA("cat".into(), "plant".into()));
+        assert!(no.proof.is_none() && no.facts.is_empty());
+    }
+
+    #[test]
+    fn unknown_subject_has_no_facts() {
+        let s = space();
+        let a = answer(&s, &Question::WhatIs("unicorn".into()));
+        assert!(a.subject.is_none());
+        assert!(a.facts.is_empty());
+    }
+
+    #[test]
+    fn what_resembles_lists_stored_then_shared_parents() {
+        let mut s = space();
+        parse::parse_input(&mut s, "cat resembles lynx");
+        let size = s.size();
+        let a = answer(&s, &Question::Resembles("cat".into()));
+        let texts: Vec<_> = a.facts.iter().map(|f| f.text.as_str()).collect();
+        assert_eq!(texts, vec!["cat resembles lynx", "cat resembles dog"]);
+        assert!(a.facts[0].atom.is_some());
+        assert!(a.facts[1].atom.is_none(), "dog is scored, not stored");
+        assert_eq!(s.size(), size);
+
+        let none = answer(&s, &Question::Resembles("unicorn".into()));
+        assert!(none.subject.is_none() && none.facts.is_empty());
+    }
+
+    #[test]
+    fn inverted_questions_are_not_confident_yeses() {
+        let s = space();
+        let config = BackwardConfig::for_rules(&{
+            let mut r = crate::rules::RuleRegistry::builtin();
+            r.set_enabled("inversion", true);
+            r
+        });
+        // are all mammals cats?
+        let q = Question::IsA("mammal".into(), "cat".into());
+        let a = answer_with(&s, &q, &config);
+        assert!(a.facts[0].tv.strength < 0.5);
+        assert_eq!(a.facts[0].text, "mammal is-not-a cat");
+    }
+
+    #[test]
+    fn near_universal_parents_are_weak_resemblance() {
+        let mut s = space();
+        crate::pln::forward_chain(&mut s, 5);
+        let a = answer(&s, &Question::Resembles("cat".into()));
+        let strength = |name: &str| {
+            a.facts
+                .iter()
+                .find(|f| f.text == format!("cat resembles {}", name))
+                .map_or(0.0, |f| f.tv.strength)
+        };
+        // Sharing only living-thing and thing is not resemblance
+        assert_eq!(strength("plant"), 0.0);
+        assert!(strength("dog") >= MIN_RESEMBLANCE);
+    }
+
+    #[test]
+    fn negated_facts_are_answered_as_denials() {
+        let mut s = space();
+        parse::parse_input(&mut s, "penguin is-a bird");
+        parse::parse_input(&mut s, "penguin is not a flier");
+        let a = answer(&s, &Question::WhatIs("penguin".into()));
+        let texts: Vec<_> = a.facts.iter().map(|f| f.text.as_str()).collect();
+        assert!(texts.contains(&"penguin is-not-a flier"), "{:?}", texts);
+        assert!(!texts.contains(&"penguin is-a flier"));
+        assert!(texts.contains(&"penguin is-a bird"));
+
+        let a = answer(&s, &Question::IsA("penguin".into(), "flier".into()));
+        assert_eq!(a.facts[0].text, "penguin is-not-a flier");
+        assert!(a.facts[0].tv.strength < 0.5);
+    }
+}
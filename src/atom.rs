@@ -1,9 +1,12 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Simple truth value: strength (probability) and confidence (weight of evidence)
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct TruthValue {
+    #[serde(rename = "s")]
     pub strength: f64,
+    #[serde(rename = "c")]
     pub confidence: f64,
 }
 
@@ -22,8 +25,41 @@ impl TruthValue {
     pub fn is_valid(&self) -> bool {
         (0.0..=1.0).contains(&self.strength) && (0.0..=1.0).contains(&self.confidence)
     }
+
+    /// Evidence count behind this value: n = K·c / (1 − c).
+    /// Confidence is capped just below 1 so certainty stays finite.
+    pub fn count(&self) -> f64 {
+        let c = self.confidence.min(MAX_CONFIDENCE);
+        EVIDENCE_K * c / (1.0 - c)
+    }
+
+    /// Truth value backed by `count` observations: c = n / (n + K)
+    pub fn from_count(strength: f64, count: f64) -> Self {
+        Self::new(strength, count / (count + EVIDENCE_K))
+    }
+
+    /// PLN revision: pool the evidence of two independent estimates.
+    /// Strength is the count-weighted mean, counts add, so confidence rises.
+    pub fn revise(&self, other: &TruthValue) -> TruthValue {
+        let (n1, n2) = (self.count(), other.count());
+        // Without evidence on one side the other stands exactly as it was
+        if n2 <= 0.0 {
+            return *self;
+        }
+        if n1 <= 0.0 {
+            return *other;
+        }
+        let n = n1 + n2;
+        Self::from_count((n1 * self.strength + n2 * other.strength) / n, n)
+    }
 }
 
+/// Evidence-count lookahead K in c = n / (n + K)
+pub const EVIDENCE_K: f64 = 1.0;
+
+/// Highest confidence used when converting to evidence counts
+const MAX_CONFIDENCE: f64 = 0.9999;
+
 impl fmt::Display for TruthValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "stv {:.2}/{:.2}", self.strength, self.confidence)
@@ -44,23 +80,151 @@ impl AttentionValue {
 }
 
 /// Types of atoms in the hypergraph
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum AtomType {
     ConceptNode,
     PredicateNode,
     InheritanceLink,
     EvaluationLink,
     ListLink,
+    /// Symmetric: the order of its two members carries no meaning
+    SimilarityLink,
+    /// Instance-of: an element and the concept it belongs to
+    MemberLink,
+    /// Conditional: the antecedent implies the consequent
+    ImplicationLink,
+    AndLink,
+    OrLink,
+    NotLink,
+    /// Placeholder bound by pattern matching, e.g. `$X`
+    VariableNode,
+    /// A numeric literal, named by its value
+    NumberNode,
+    /// A procedure or function, as opposed to a predicate
+    SchemaNode,
+    /// A link that holds within a context concept
+    ContextLink,
+    /// Learned association: its members tend to share the attentional focus
+    HebbianLink,
+    /// Learned association: the second member tends to be in the focus
+    /// when the first one is
+    AsymmetricHebbianLink,
+}
+
+/// How many atoms a link type takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(self, n: usize) -> bool {
+        match self {
+            Arity::Exactly(k) => n == k,
+            Arity::AtLeast(k) => n >= k,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arity::Exactly(k) => write!(f, "exactly {}", k),
+            Arity::AtLeast(k) => write!(f, "at least {}", k),
+        }
+    }
 }
 
 impl AtomType {
     pub fn is_node(self) -> bool {
-        matches!(self, AtomType::ConceptNode | AtomType::PredicateNode)
+        matches!(
+            self,
+            AtomType::ConceptNode
+                | AtomType::PredicateNode
+                | AtomType::VariableNode
+                | AtomType::NumberNode
+                | AtomType::SchemaNode
+        )
     }
 
     pub fn is_link(self) -> bool {
         !self.is_node()
     }
+
+    /// Links whose outgoing set is unordered: A↔B and B↔A are one atom
+    pub fn is_unordered(self) -> bool {
+        matches!(
+            self,
+            AtomType::SimilarityLink | AtomType::AndLink | AtomType::OrLink | AtomType::HebbianLink
+        )
+    }
+
+    /// Links ECAN learns from co-activation rather than the user asserting
+    pub fn is_hebbian(self) -> bool {
+        matches!(
+            self,
+            AtomType::HebbianLink | AtomType::AsymmetricHebbianLink
+        )
+    }
+
+    /// Number of outgoing atoms a link takes; None for nodes
+    pub fn arity(self) -> Option<Arity> {
+        match self {
+            AtomType::InheritanceLink
+            | AtomType::EvaluationLink
+            | AtomType::SimilarityLink
+            | AtomType::MemberLink
+            | AtomType::ImplicationLink
+            | AtomType::ContextLink
+            | AtomType::HebbianLink
+            | AtomType::AsymmetricHebbianLink => Some(Arity::Exactly(2)),
+            AtomType::AndLink | AtomType::OrLink => Some(Arity::AtLeast(2)),
+            AtomType::NotLink => Some(Arity::Exactly(1)),
+            AtomType::ListLink => Some(Arity::AtLeast(0)),
+            AtomType::ConceptNode
+            | AtomType::PredicateNode
+            | AtomType::VariableNode
+            | AtomType::NumberNode
+            | AtomType::SchemaNode => None,
+        }
+    }
+
+    /// Type required at an outgoing position, if the link constrains it:
+    /// an EvaluationLink is headed by its predicate, a MemberLink ends in
+    /// the concept its element belongs to, a ContextLink starts with the
+    /// context
+    pub fn member_type(self, position: usize) -> Option<AtomType> {
+        match (self, position) {
+            (AtomType::EvaluationLink, 0) => Some(AtomType::PredicateNode),
+            (AtomType::MemberLink, 1) | (AtomType::ContextLink, 0) => Some(AtomType::ConceptNode),
+            _ => None,
+        }
+    }
+
+    /// Inverse of `Display`: parse a type name such as `"ConceptNode"`
+    pub fn from_name(name: &str) -> Option<AtomType> {
+        match name {
+            "ConceptNode" => Some(AtomType::ConceptNode),
+            "PredicateNode" => Some(AtomType::PredicateNode),
+            "InheritanceLink" => Some(AtomType::InheritanceLink),
+            "EvaluationLink" => Some(AtomType::EvaluationLink),
+            "ListLink" => Some(AtomType::ListLink),
+            "SimilarityLink" => Some(AtomType::SimilarityLink),
+            "MemberLink" => Some(AtomType::MemberLink),
+            "ImplicationLink" => Some(AtomType::ImplicationLink),
+            "AndLink" => Some(AtomType::AndLink),
+            "OrLink" => Some(AtomType::OrLink),
+            "NotLink" => Some(AtomType::NotLink),
+            "VariableNode" => Some(AtomType::VariableNode),
+            "NumberNode" => Some(AtomType::NumberNode),
+            "SchemaNode" => Some(AtomType::SchemaNode),
+            "ContextLink" => Some(AtomType::ContextLink),
+            "HebbianLink" => Some(AtomType::HebbianLink),
+            "AsymmetricHebbianLink" => Some(AtomType::AsymmetricHebbianLink),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for AtomType {
@@ -71,6 +235,18 @@ impl fmt::Display for AtomType {
             AtomType::InheritanceLink => write!(f, "InheritanceLink"),
             AtomType::EvaluationLink => write!(f, "EvaluationLink"),
             AtomType::ListLink => write!(f, "ListLink"),
+            AtomType::SimilarityLink => write!(f, "SimilarityLink"),
+            AtomType::MemberLink => write!(f, "MemberLink"),
+            AtomType::ImplicationLink => write!(f, "ImplicationLink"),
+            AtomType::AndLink => write!(f, "AndLink"),
+            AtomType::OrLink => write!(f, "OrLink"),
+            AtomType::NotLink => write!(f, "NotLink"),
+            AtomType::VariableNode => write!(f, "VariableNode"),
+            AtomType::NumberNode => write!(f, "NumberNode"),
+            AtomType::SchemaNode => write!(f, "SchemaNode"),
+            AtomType::ContextLink => write!(f, "ContextLink"),
+            AtomType::HebbianLink => write!(f, "HebbianLink"),
+            AtomType::AsymmetricHebbianLink => write!(f, "AsymmetricHebbianLink"),
         }
     }
 }
@@ -145,6 +321,53 @@ mod tests {
         assert!(AtomType::InheritanceLink.is_link());
         assert!(AtomType::EvaluationLink.is_link());
         assert!(AtomType::ListLink.is_link());
+        assert!(AtomType::SimilarityLink.is_link());
+        assert!(AtomType::SimilarityLink.is_unordered());
+        assert!(!AtomType::InheritanceLink.is_unordered());
+        for t in [
+            AtomType::VariableNode,
+            AtomType::NumberNode,
+            AtomType::SchemaNode,
+        ] {
+            assert!(t.is_node() && t.arity().is_none());
+        }
+        assert!(AtomType::AndLink.is_unordered() && AtomType::OrLink.is_unordered());
+        assert!(!AtomType::ImplicationLink.is_unordered());
+        assert!(AtomType::HebbianLink.is_unordered() && AtomType::HebbianLink.is_hebbian());
+        assert!(!AtomType::AsymmetricHebbianLink.is_unordered());
+        assert_eq!(AtomType::NotLink.arity(), Some(Arity::Exactly(1)));
+        assert_eq!(AtomType::AndLink.arity(), Some(Arity::AtLeast(2)));
+        assert_eq!(
+            AtomType::MemberLink.member_type(1),
+            Some(AtomType::ConceptNode)
+        );
+        assert_eq!(AtomType::MemberLink.member_type(0), None);
+    }
+
+    #[test]
+    fn test_atom_type_name_roundtrip() {
+        for t in [
+            AtomType::ConceptNode,
+            AtomType::PredicateNode,
+            AtomType::InheritanceLink,
+            AtomType::EvaluationLink,
+            AtomType::ListLink,
+            AtomType::SimilarityLink,
+            AtomType::MemberLink,
+            AtomType::ImplicationLink,
+            AtomType::AndLink,
+            AtomType::OrLink,
+            AtomType::NotLink,
+            AtomType::VariableNode,
+            AtomType::NumberNode,
+            AtomType::SchemaNode,
+            AtomType::ContextLink,
+            AtomType::HebbianLink,
+            AtomType::AsymmetricHebbianLink,
+        ] {
+            assert_eq!(AtomType::from_name(&t.to_string()), Some(t));
+        }
+        assert_eq!(AtomType::from_name("NopeNode"), None);
     }
 
     #[test]
@@ -0,0 +1,485 @@
+//! Atomese — Scheme s-expression import/export
+//! Reads and writes the OpenCog surface syntax, e.g.
+//! `(InheritanceLink (stv 0.9 0.8) (ConceptNode "cat") (ConceptNode "mammal"))`.
+//! A `(stv s c)` may appear anywhere among an atom's arguments; atoms without
+//! one get `TruthValue::default_tv()`, which never overrides an existing value.
+
+use std::fmt;
+
+use crate::atom::*;
+use crate::atomspace::{check_signature, AtomSpace};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtomeseError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for AtomeseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "atomese line {}: {}", self.line, self.msg)
+    }
+}
+
+impl std::error::Error for AtomeseError {}
+
+/// Raw s-expression with the line it started on
+#[derive(Debug, Clone, PartialEq)]
+pub enum SExpr {
+    Symbol(String, usize),
+    Str(String, usize),
+    List(Vec<SExpr>, usize),
+}
+
+impl SExpr {
+    pub fn line(&self) -> usize {
+        match self {
+            SExpr::Symbol(_, l) | SExpr::Str(_, l) | SExpr::List(_, l) => *l,
+        }
+    }
+}
+
+fn err<T>(line: usize, msg: impl Into<String>) -> Result<T, AtomeseError> {
+    Err(AtomeseError {
+        line,
+        msg: msg.into(),
+    })
+}
+
+/// Read every top-level s-expression in `text`. `;` starts a comment.
+pub fn read_sexprs(text: &str) -> Result<Vec<SExpr>, AtomeseError> {
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    // Stack of open lists: (items, starting line)
+    let mut stack: Vec<(Vec<SExpr>, usize)> = Vec::new();
+    let mut top = Vec::new();
+
+    while let Some(c) = chars.next() {
+        let item = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            ';' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+                continue;
+            }
+            '(' => {
+                stack.push((Vec::new(), line));
+                continue;
+            }
+            ')' => match stack.pop() {
+                Some((items, start)) => SExpr::List(items, start),
+                None => return err(line, "unexpected ')'"),
+            },
+            '"' => {
+                let start = line;
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(e) => s.push(e),
+                            None => return err(start, "unterminated string"),
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            s.push(c);
+                        }
+                        None => return err(start, "unterminated string"),
+                    }
+                }
+                SExpr::Str(s, start)
+            }
+            c => {
+                let mut s = c.to_string();
+                while let Some(&n) = chars.peek() {
+                    if n.is_whitespace() || n == '(' || n == ')' || n == '"' || n == ';' {
+                        break;
+                    }
+                    s.push(n);
+                    chars.next();
+                }
+                SExpr::Symbol(s, line)
+            }
+        };
+        match stack.last_mut() {
+            Some((items, _)) => items.push(item),
+            None => top.push(item),
+        }
+    }
+    if let Some((_, start)) = stack.last() {
+        return err(*start, "unclosed '('");
+    }
+    Ok(top)
+}
+
+/// A validated atom description, ready to be added to a space
+#[derive(Debug, Clone, PartialEq)]
+enum Spec {
+    Node {
+        atom_type: AtomType,
+        name: String,
+        tv: Option<TruthValue>,
+    },
+    Link {
+        atom_type: AtomType,
+        outgoing: Vec<Spec>,
+        tv: Option<TruthValue>,
+    },
+}
+
+impl Spec {
+    fn atom_type(&self) -> AtomType {
+        match self {
+            Spec::Node { atom_type, .. } | Spec::Link { atom_type, .. } => *atom_type,
+        }
+    }
+}
+
+/// The items of an `(stv s c)` form, if `expr` is one
+fn as_stv(expr: &SExpr) -> Option<(&[SExpr], usize)> {
+    let SExpr::List(items, line) = expr else {
+        return None;
+    };
+    match items.first() {
+        Some(SExpr::Symbol(s, _)) if s == "stv" => Some((items, *line)),
+        _ => None,
+    }
+}
+
+fn parse_stv(items: &[SExpr], line: usize) -> Result<TruthValue, AtomeseError> {
+    let nums: Vec<f64> = items[1..]
+        .iter()
+        .map(|e| match e {
+            SExpr::Symbol(s, _) => s.parse::<f64>().ok(),
+            _ => None,
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(|| AtomeseError {
+            line,
+            msg: "stv expects numbers".into(),
+        })?;
+    match nums.as_slice() {
+        [s, c] if (0.0..=1.0).contains(s) && (0.0..=1.0).contains(c) => Ok(TruthValue::new(*s, *c)),
+        [_, _] => err(line, "stv values must lie in [0, 1]"),
+        _ => err(line, "stv expects exactly two values"),
+    }
+}
+
+fn to_spec(expr: &SExpr) -> Result<Spec, AtomeseError> {
+    let SExpr::List(items, line) = expr else {
+        return err(expr.line(), "expected an atom expression");
+    };
+    let line = *line;
+    let Some(SExpr::Symbol(head, _)) = items.first() else {
+        return err(line, "atom expression must start with a type name");
+    };
+    let Some(atom_type) = AtomType::from_name(head) else {
+        return err(line, format!("unknown atom type '{}'", head));
+    };
+
+    let mut tv = None;
+    let mut name = None;
+    let mut outgoing = Vec::new();
+    for arg in &items[1..] {
+        if let Some((inner, l)) = as_stv(arg) {
+            if tv.is_some() {
+                return err(l, "duplicate stv");
+            }
+            tv = Some(parse_stv(inner, l)?);
+            continue;
+        }
+        match arg {
+            SExpr::Str(s, l) => {
+                if !atom_type.is_node() {
+                    return err(*l, format!("{} takes atoms, not a name", atom_type));
+                }
+                if name.replace(s.clone()).is_some() {
+                    return err(*l, "node has more than one name");
+                }
+            }
+            SExpr::List(..) => {
+                if atom_type.is_node() {
+                    return err(arg.line(), format!("{} takes a name, not atoms", atom_type));
+                }
+                outgoing.push(to_spec(arg)?);
+            }
+            SExpr::Symbol(s, l) => return err(*l, format!("unexpected symbol '{}'", s)),
+        }
+    }
+
+    if atom_type.is_node() {
+        match name {
+            Some(name) => Ok(Spec::Node {
+                atom_type,
+                name,
+                tv,
+            }),
+            None => err(line, format!("{} needs a name", atom_type)),
+        }
+    } else {
+        let members: Vec<AtomType> = outgoing.iter().map(Spec::atom_type).collect();
+        if let Err(e) = check_signature(atom_type, &members) {
+            return err(line, e.to_string());
+        }
+        Ok(Spec::Link {
+            atom_type,
+            outgoing,
+            tv,
+        })
+    }
+}
+
+fn add_spec(space: &mut AtomSpace, spec: &Spec) -> AtomId {
+    match spec {
+        Spec::Node {
+            atom_type,
+            name,
+            tv,
+        } => {
+            space
+                .add_node(*atom_type, name, tv.unwrap_or_else(TruthValue::default_tv))
+                .0
+        }
+        Spec::Link {
+            atom_type,
+            outgoing,
+            tv,
+        } => {
+            let ids = outgoing.iter().map(|o| add_spec(space, o)).collect();
+            space
+                .add_link(*atom_type, ids, tv.unwrap_or_else(TruthValue::default_tv))
+                .0
+        }
+    }
+}
+
+/// Import Atomese into the space. The whole text is validated before any
+/// atom is added, so a syntax error leaves the space untouched.
+/// Returns the ids of the top-level atoms in input order.
+pub fn import(space: &mut AtomSpace, text: &str) -> Result<Vec<AtomId>, AtomeseError> {
+    let specs = read_sexprs(text)?
+        .iter()
+        .map(to_spec)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(specs.iter().map(|s| add_spec(space, s)).collect())
+}
+
+fn quote(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 2);
+    out.push('"');
+    for c in name.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+fn write_atom(space: &AtomSpace, id: AtomId, with_tv: bool) -> Option<String> {
+    let atom = space.get(id)?;
+    let tv = if with_tv {
+        format!(" (stv {} {})", atom.tv.strength, atom.tv.confidence)
+    } else {
+        String::new()
+    };
+    if let Some(ref name) = atom.name {
+        return Some(format!("({} {}{})", atom.atom_type, quote(name), tv));
+    }
+    let parts = atom
+        .outgoing
+        .iter()
+        .map(|&o| write_atom(space, o, false))
+        .collect::<Option<Vec<_>>>()?;
+    if parts.is_empty() {
+        Some(format!("({}{})", atom.atom_type, tv))
+    } else {
+        Some(format!("({}{} {})", atom.atom_type, tv, parts.join(" ")))
+    }
+}
+
+/// Atomese for a single atom, including its truth value.
+/// Returns None if the atom (or anything it references) is missing.
+pub fn to_atomese(space: &AtomSpace, id: AtomId) -> Option<String> {
+    write_atom(space, id, true)
+}
+
+/// Export the whole space, one atom per line in id order. Every atom carries
+/// its own `stv` exactly once; nested references omit it, so importing the
+/// result reproduces the truth values without double-counting evidence.
+/// Links with dangling references are skipped.
+pub fn export(space: &AtomSpace) -> String {
+    let mut out = String::new();
+    for atom in space.all_atoms_sorted() {
+        if let Some(line) = to_atomese(space, atom.id) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontology;
+
+    #[test]
+    fn import_inheritance_example() {
+        let mut space = AtomSpace::new();
+        let ids = import(
+            &mut space,
+            r#"(InheritanceLink (stv 0.9 0.8) (ConceptNode "cat") (ConceptNode "mammal"))"#,
+        )
+        .unwrap();
+        assert_eq!(ids.len(), 1);
+        let cat = space.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let mammal = space.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let link = space.get(ids[0]).unwrap();
+        assert_eq!(link.outgoing, vec![cat, mammal]);
+        assert_eq!(link.tv, TruthValue::new(0.9, 0.8));
+        assert_eq!(space.get(cat).unwrap().tv, TruthValue::default_tv());
+    }
+
+    #[test]
+    fn import_nested_evaluation_with_node_tv() {
+        let mut space = AtomSpace::new();
+        let text = r#"
+            ; cat likes fish
+            (EvaluationLink (stv 0.7 0.3)
+                (PredicateNode "likes" (stv 0.7 0.4))
+                (ListLink (ConceptNode "cat") (ConceptNode "fish")))
+        "#;
+        import(&mut space, text).unwrap();
+        assert_eq!(space.size(), 5);
+        let likes = space.find_node(AtomType::PredicateNode, "likes").unwrap();
+        assert_eq!(space.get(likes).unwrap().tv, TruthValue::new(0.7, 0.4));
+        assert_eq!(space.get_by_type(AtomType::ListLink).len(), 1);
+    }
+
+    #[test]
+    fn export_roundtrips_ontology() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let text = export(&space);
+        let mut copy = AtomSpace::new();
+        import(&mut copy, &text).unwrap();
+        assert_eq!(copy.size(), space.size());
+        for atom in space.all_atoms_sorted() {
+            let other = copy.get(atom.id).unwrap();
+            assert_eq!(other.atom_type, atom.atom_type);
+            assert_eq!(other.name, atom.name);
+            assert_eq!(other.outgoing, atom.outgoing);
+            assert_eq!(other.tv, atom.tv);
+        }
+    }
+
+    #[test]
+    fn writer_format() {
+        let mut space = AtomSpace::new();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "cat", TruthValue::new(0.9, 0.85));
+        let (b, _) = space.add_node(
+            AtomType::ConceptNode,
+            "say \"hi\"",
+            TruthValue::new(1.0, 0.0),
+        );
+        let (l, _) = space.add_link(
+            AtomType::InheritanceLink,
+            vec![a, b],
+            TruthValue::new(0.95, 0.9),
+        );
+        assert_eq!(
+            to_atomese(&space, a).unwrap(),
+            r#"(ConceptNode "cat" (stv 0.9 0.85))"#
+        );
+        assert_eq!(
+            to_atomese(&space, l).unwrap(),
+            r#"(InheritanceLink (stv 0.95 0.9) (ConceptNode "cat") (ConceptNode "say \"hi\""))"#
+        );
+        let mut copy = AtomSpace::new();
+        import(&mut copy, &export(&space)).unwrap();
+        assert!(copy
+            .find_node(AtomType::ConceptNode, "say \"hi\"")
+            .is_some());
+    }
+
+    #[test]
+    fn errors_leave_space_untouched() {
+        let mut space = AtomSpace::new();
+        let text = "(ConceptNode \"ok\")\n(FooNode \"bad\")";
+        let e = import(&mut space, text).unwrap_err();
+        assert_eq!(e.line, 2);
+        assert!(e.msg.contains("FooNode"));
+        assert_eq!(space.size(), 0);
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let mut space = AtomSpace::new();
+        for bad in [
+            "(ConceptNode \"cat\"",
+            "(ConceptNode \"cat\"))",
+            "(ConceptNode)",
+            "(InheritanceLink \"cat\")",
+            "(ConceptNode \"cat\" (stv 1.5 0.2))",
+            "(ConceptNode \"cat\" (stv 0.5))",
+            "(ConceptNode \"unterminated)",
+        ] {
+            assert!(import(&mut space, bad).is_err(), "should reject {}", bad);
+        }
+    }
+
+    #[test]
+    fn link_signatures_checked_before_import() {
+        let mut space = AtomSpace::new();
+        let e = import(
+            &mut space,
+            "(ConceptNode \"ok\")\n(NotLink (ConceptNode \"a\") (ConceptNode \"b\"))",
+        )
+        .unwrap_err();
+        assert_eq!(e.line, 2);
+        assert!(e.msg.contains("exactly 1"), "{}", e.msg);
+        let e = import(
+            &mut space,
+            r#"(EvaluationLink (ConceptNode "likes") (ListLink (ConceptNode "cat")))"#,
+        )
+        .unwrap_err();
+        assert!(e.msg.contains("PredicateNode"), "{}", e.msg);
+        assert_eq!(space.size(), 0);
+
+        let ids = import(
+            &mut space,
+            r#"(MemberLink (ConceptNode "tom") (ConceptNode "cat"))
+               (ImplicationLink (AndLink (VariableNode "$X") (NumberNode "3"))
+                                (NotLink (SchemaNode "run")))"#,
+        )
+        .unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(space.get_by_type(AtomType::AndLink).len(), 1);
+    }
+
+    #[test]
+    fn export_skips_dangling_links() {
+        use crate::atomspace::RemovalPolicy;
+        let mut space = AtomSpace::new();
+        import(
+            &mut space,
+            r#"(InheritanceLink (stv 0.9 0.8) (ConceptNode "cat") (ConceptNode "mammal"))"#,
+        )
+        .unwrap();
+        let mammal = space.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        space.remove_atom(mammal, RemovalPolicy::Orphan).unwrap();
+        assert_eq!(export(&space).lines().count(), 1);
+    }
+}
@@ -1,5 +1,176 @@
 use crate::atom::*;
+use crate::wal::Mutation;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+
+/// How a derived atom was produced: the rule, its premises and the truth
+/// value the rule computed at the time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub rule: String,
+    pub premises: Vec<AtomId>,
+    pub tv: TruthValue,
+}
+
+/// What to do with links that still reference an atom being removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalPolicy {
+    /// Refuse to remove an atom that has incoming links
+    Refuse,
+    /// Remove every link that (transitively) references the atom
+    Cascade,
+    /// Remove only the atom; dependent links keep a dangling reference
+    /// that tikkun reports as an orphan
+    Orphan,
+}
+
+/// How a re-asserted truth value combines with the one already stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+    /// PLN revision: pool both as independent evidence
+    #[default]
+    Revision,
+    /// Keep whichever value has the higher confidence
+    KeepMax,
+}
+
+impl MergePolicy {
+    /// Parse `"revision"` or `"keep-max"`
+    pub fn from_name(name: &str) -> Option<MergePolicy> {
+        match name {
+            "revision" => Some(MergePolicy::Revision),
+            "keep-max" => Some(MergePolicy::KeepMax),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoveError {
+    NotFound(AtomId),
+    HasIncoming { id: AtomId, incoming: usize },
+}
+
+impl fmt::Display for RemoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoveError::NotFound(id) => write!(f, "atom {:04x} not found", id),
+            RemoveError::HasIncoming { id, incoming } => {
+                write!(f, "atom {:04x} is referenced by {} link(s)", id, incoming)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RemoveError {}
+
+/// Why an outgoing set doesn't fit a link type's signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    NotALink(AtomType),
+    Arity {
+        atom_type: AtomType,
+        expected: Arity,
+        found: usize,
+    },
+    MemberType {
+        atom_type: AtomType,
+        position: usize,
+        expected: AtomType,
+        found: AtomType,
+    },
+    /// An outgoing atom isn't in the space
+    NotFound(AtomId),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::NotALink(t) => write!(f, "{} is not a link type", t),
+            SignatureError::Arity {
+                atom_type,
+                expected,
+                found,
+            } => write!(f, "{} takes {} atom(s), got {}", atom_type, expected, found),
+            SignatureError::MemberType {
+                atom_type,
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} expects a {} at position {}, got a {}",
+                atom_type, expected, position, found
+            ),
+            SignatureError::NotFound(id) => write!(f, "atom {:04x} not found", id),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Check member types against a link type's arity and positional types
+pub fn check_signature(atom_type: AtomType, members: &[AtomType]) -> Result<(), SignatureError> {
+    let arity = atom_type
+        .arity()
+        .ok_or(SignatureError::NotALink(atom_type))?;
+    if !arity.accepts(members.len()) {
+        return Err(SignatureError::Arity {
+            atom_type,
+            expected: arity,
+            found: members.len(),
+        });
+    }
+    for (position, &found) in members.iter().enumerate() {
+        match atom_type.member_type(position) {
+            Some(expected) if expected != found => {
+                return Err(SignatureError::MemberType {
+                    atom_type,
+                    position,
+                    expected,
+                    found,
+                })
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Key of a link in the link index. Unordered links are keyed by their
+/// sorted outgoing set, so either order finds the same atom; the atom keeps
+/// the order it was first added with.
+fn link_key(atom_type: AtomType, outgoing: &[AtomId]) -> (AtomType, Vec<AtomId>) {
+    let mut outgoing = outgoing.to_vec();
+    if atom_type.is_unordered() {
+        outgoing.sort_unstable();
+    }
+    (atom_type, outgoing)
+}
+
+/// Total STI in circulation: the bank's balance plus every atom's STI
+pub const STI_FUNDS: f64 = 10_000.0;
+/// Total LTI in circulation, likewise
+pub const LTI_FUNDS: f64 = 10_000.0;
+
+/// The attention bank: importance no atom currently holds. Every change to
+/// an atom's STI or LTI moves funds to or from it, so the bank's balance
+/// plus the atoms' holdings always add up to the fixed funds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AttentionBank {
+    pub sti: f64,
+    pub lti: f64,
+}
+
+impl Default for AttentionBank {
+    fn default() -> Self {
+        Self {
+            sti: STI_FUNDS,
+            lti: LTI_FUNDS,
+        }
+    }
+}
 
 /// The AtomSpace hypergraph — stores atoms with indexed lookups
 pub struct AtomSpace {
@@ -7,15 +178,31 @@ pub struct AtomSpace {
     next_id: AtomId,
     // Index: (type, name) → id for nodes
     node_index: HashMap<(AtomType, String), AtomId>,
-    // Index: (type, outgoing) → id for links
+    // Index: (type, outgoing) → id for links; see `link_key`
     link_index: HashMap<(AtomType, Vec<AtomId>), AtomId>,
     // Index: type → list of ids
     type_index: HashMap<AtomType, Vec<AtomId>>,
     // Incoming set: atom_id → links that reference it
     incoming: HashMap<AtomId, Vec<AtomId>>,
+    // Incoming by position: (atom_id, index in outgoing) → links
+    incoming_at: HashMap<(AtomId, usize), Vec<AtomId>>,
+    // Derivation records for inferred atoms; asserted atoms have none
+    provenance: HashMap<AtomId, Provenance>,
+    // Mutations recorded since the last `take_journal` (None = not journaling)
+    journal: Option<Vec<Mutation>>,
+    merge_policy: MergePolicy,
+    // Per inference rule: links with id ≥ this have not yet been paired
+    inference_watermarks: HashMap<String, AtomId>,
+    bank: AttentionBank,
     pub turn: u32,
 }
 
+impl Default for AtomSpace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AtomSpace {
     pub fn new() -> Self {
         Self {
@@ -25,10 +212,57 @@ impl AtomSpace {
             link_index: HashMap::new(),
             type_index: HashMap::new(),
             incoming: HashMap::new(),
+            incoming_at: HashMap::new(),
+            provenance: HashMap::new(),
+            journal: None,
+            merge_policy: MergePolicy::default(),
+            inference_watermarks: HashMap::new(),
+            bank: AttentionBank::default(),
             turn: 0,
         }
     }
 
+    /// Rebuild a space from stored atoms, regenerating every index.
+    /// Used by snapshot loading; ids and `next_id` are kept verbatim. The
+    /// bank holds whatever the atoms don't; `set_bank` restores a stored one.
+    pub fn from_parts(atoms: Vec<Atom>, next_id: AtomId, turn: u32) -> Self {
+        let mut space = Self::new();
+        space.turn = turn;
+        for atom in atoms {
+            let id = atom.id;
+            space.bank.sti -= atom.av.sti;
+            space.bank.lti -= atom.av.lti;
+            if let Some(ref name) = atom.name {
+                space.node_index.insert((atom.atom_type, name.clone()), id);
+            } else {
+                space
+                    .link_index
+                    .insert(link_key(atom.atom_type, &atom.outgoing), id);
+                space.index_incoming(id, &atom.outgoing);
+            }
+            space.type_index.entry(atom.atom_type).or_default().push(id);
+            space.next_id = space.next_id.max(id + 1);
+            space.atoms.insert(id, atom);
+        }
+        space.next_id = space.next_id.max(next_id);
+        space
+    }
+
+    /// Id that the next new atom will receive
+    pub fn next_id(&self) -> AtomId {
+        self.next_id
+    }
+
+    /// First atom id that forward chaining with `rule` has not yet used as
+    /// a premise seed. Not persisted: a restored space chains in full once.
+    pub fn inference_watermark(&self, rule: &str) -> AtomId {
+        self.inference_watermarks.get(rule).copied().unwrap_or(0)
+    }
+
+    pub fn set_inference_watermark(&mut self, rule: &str, id: AtomId) {
+        self.inference_watermarks.insert(rule.to_string(), id);
+    }
+
     pub fn size(&self) -> usize {
         self.atoms.len()
     }
@@ -37,20 +271,154 @@ impl AtomSpace {
         self.atoms.get(&id)
     }
 
+    /// Direct mutable access. Changes made through this bypass the journal;
+    /// use `set_tv`/`set_sti` for anything that must survive a replay.
     pub fn get_mut(&mut self, id: AtomId) -> Option<&mut Atom> {
         self.atoms.get_mut(&id)
     }
 
+    /// Start recording mutations for `take_journal`
+    pub fn enable_journal(&mut self) {
+        self.journal.get_or_insert_with(Vec::new);
+    }
+
+    /// Drain mutations recorded since the previous call
+    pub fn take_journal(&mut self) -> Vec<Mutation> {
+        self.journal
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Put entries from `take_journal` back ahead of anything recorded
+    /// since, so a failed write doesn't lose them
+    pub fn restore_journal(&mut self, mut entries: Vec<Mutation>) {
+        if let Some(journal) = self.journal.as_mut() {
+            entries.append(journal);
+            *journal = entries;
+        }
+    }
+
+    fn record(&mut self, m: Mutation) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(m);
+        }
+    }
+
+    /// Advance the dialogue turn for a new input. Returns the new turn.
+    pub fn begin_turn(&mut self, input: &str) -> u32 {
+        self.turn += 1;
+        self.record(Mutation::Input {
+            turn: self.turn,
+            text: input.to_string(),
+        });
+        self.turn
+    }
+
+    pub fn set_tv(&mut self, id: AtomId, tv: TruthValue) {
+        let Some(atom) = self.atoms.get_mut(&id) else {
+            return;
+        };
+        if atom.tv == tv {
+            return;
+        }
+        atom.tv = tv;
+        self.record(Mutation::SetTv { id, tv });
+    }
+
+    /// Record how `id` was derived, replacing any earlier record
+    pub fn set_provenance(&mut self, id: AtomId, provenance: Provenance) {
+        if !self.atoms.contains_key(&id) {
+            return;
+        }
+        self.record(Mutation::Derive {
+            id,
+            rule: provenance.rule.clone(),
+            premises: provenance.premises.clone(),
+            tv: provenance.tv,
+        });
+        self.provenance.insert(id, provenance);
+    }
+
+    /// Treat a derived atom as asserted from now on
+    pub fn clear_provenance(&mut self, id: AtomId) {
+        if self.provenance.remove(&id).is_some() {
+            self.record(Mutation::Assert { id });
+        }
+    }
+
+    /// Derivation record for an inferred atom; None for asserted atoms
+    pub fn provenance(&self, id: AtomId) -> Option<&Provenance> {
+        self.provenance.get(&id)
+    }
+
+    /// Set an atom's STI, paying the difference from (or back into) the bank
+    pub fn set_sti(&mut self, id: AtomId, sti: f64) {
+        let Some(atom) = self.atoms.get_mut(&id) else {
+            return;
+        };
+        if atom.av.sti == sti {
+            return;
+        }
+        self.bank.sti -= sti - atom.av.sti;
+        atom.av.sti = sti;
+        self.record(Mutation::SetSti { id, sti });
+    }
+
+    /// Set an atom's LTI, paying the difference from (or back into) the bank
+    pub fn set_lti(&mut self, id: AtomId, lti: f64) {
+        let Some(atom) = self.atoms.get_mut(&id) else {
+            return;
+        };
+        if atom.av.lti == lti {
+            return;
+        }
+        self.bank.lti -= lti - atom.av.lti;
+        atom.av.lti = lti;
+        self.record(Mutation::SetLti { id, lti });
+    }
+
+    pub fn bank(&self) -> AttentionBank {
+        self.bank
+    }
+
+    /// Restore a stored bank balance; used by snapshot loading
+    pub fn set_bank(&mut self, bank: AttentionBank) {
+        self.bank = bank;
+    }
+
+    pub fn merge_policy(&self) -> MergePolicy {
+        self.merge_policy
+    }
+
+    pub fn set_merge_policy(&mut self, policy: MergePolicy) {
+        self.merge_policy = policy;
+    }
+
+    /// Merge a re-asserted truth value into an existing atom. A derived
+    /// truth value is an estimate from other atoms rather than evidence, so
+    /// asserting a derived atom replaces it and drops the provenance.
+    fn merge_tv(&mut self, id: AtomId, tv: TruthValue) {
+        let Some(old) = self.atoms.get(&id).map(|atom| atom.tv) else {
+            return;
+        };
+        if tv.confidence > 0.0 && self.provenance.contains_key(&id) {
+            self.clear_provenance(id);
+            self.set_tv(id, tv);
+            return;
+        }
+        match self.merge_policy {
+            MergePolicy::Revision => self.set_tv(id, old.revise(&tv)),
+            MergePolicy::KeepMax if tv.confidence > old.confidence => self.set_tv(id, tv),
+            MergePolicy::KeepMax => {}
+        }
+    }
+
     /// Add or retrieve a node. Returns (id, is_new).
     pub fn add_node(&mut self, atom_type: AtomType, name: &str, tv: TruthValue) -> (AtomId, bool) {
         let key = (atom_type, name.to_string());
         if let Some(&id) = self.node_index.get(&key) {
-            // Merge: keep higher confidence
-            if let Some(atom) = self.atoms.get_mut(&id) {
-                if tv.confidence > atom.tv.confidence {
-                    atom.tv = tv;
-                }
-            }
+            self.merge_tv(id, tv);
             return (id, false);
         }
 
@@ -60,24 +428,61 @@ impl AtomSpace {
         self.atoms.insert(id, atom);
         self.node_index.insert(key, id);
         self.type_index.entry(atom_type).or_default().push(id);
+        self.record(Mutation::AddNode {
+            id,
+            atom_type,
+            name: name.to_string(),
+            tv,
+        });
         (id, true)
     }
 
     /// Add or retrieve a link. Returns (id, is_new).
+    /// Panics if the outgoing set doesn't fit the type's signature; use
+    /// `try_add_link` for links built from outside input.
     pub fn add_link(
         &mut self,
         atom_type: AtomType,
         outgoing: Vec<AtomId>,
         tv: TruthValue,
     ) -> (AtomId, bool) {
-        let key = (atom_type, outgoing.clone());
+        match self.try_add_link(atom_type, outgoing, tv) {
+            Ok(added) => added,
+            Err(e) => panic!("invalid link: {}", e),
+        }
+    }
+
+    /// Check that a link of `atom_type` over `outgoing` fits the type's
+    /// signature and that every member exists
+    pub fn check_link(
+        &self,
+        atom_type: AtomType,
+        outgoing: &[AtomId],
+    ) -> Result<(), SignatureError> {
+        let members = outgoing
+            .iter()
+            .map(|id| {
+                self.atoms
+                    .get(id)
+                    .map(|a| a.atom_type)
+                    .ok_or(SignatureError::NotFound(*id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        check_signature(atom_type, &members)
+    }
+
+    /// Add or retrieve a link after checking its signature
+    pub fn try_add_link(
+        &mut self,
+        atom_type: AtomType,
+        outgoing: Vec<AtomId>,
+        tv: TruthValue,
+    ) -> Result<(AtomId, bool), SignatureError> {
+        self.check_link(atom_type, &outgoing)?;
+        let key = link_key(atom_type, &outgoing);
         if let Some(&id) = self.link_index.get(&key) {
-            if let Some(atom) = self.atoms.get_mut(&id) {
-                if tv.confidence > atom.tv.confidence {
-                    atom.tv = tv;
-                }
-            }
-            return (id, false);
+            self.merge_tv(id, tv);
+            return Ok((id, false));
         }
 
         let id = self.next_id;
@@ -86,10 +491,112 @@ impl AtomSpace {
         self.atoms.insert(id, atom);
         self.link_index.insert(key, id);
         self.type_index.entry(atom_type).or_default().push(id);
-        for &target in &outgoing {
+        self.index_incoming(id, &outgoing);
+        self.record(Mutation::AddLink {
+            id,
+            atom_type,
+            outgoing,
+            tv,
+        });
+        Ok((id, true))
+    }
+
+    /// Remove an atom, handling its incoming links according to `policy`.
+    /// Returns the ids of every atom removed, dependents first.
+    pub fn remove_atom(
+        &mut self,
+        id: AtomId,
+        policy: RemovalPolicy,
+    ) -> Result<Vec<AtomId>, RemoveError> {
+        if !self.atoms.contains_key(&id) {
+            return Err(RemoveError::NotFound(id));
+        }
+        let incoming = self.get_incoming(id);
+        match policy {
+            RemovalPolicy::Refuse if !incoming.is_empty() => Err(RemoveError::HasIncoming {
+                id,
+                incoming: incoming.len(),
+            }),
+            RemovalPolicy::Refuse | RemovalPolicy::Orphan => {
+                self.detach(id);
+                Ok(vec![id])
+            }
+            RemovalPolicy::Cascade => {
+                // Post-order walk so links are removed before what they reference
+                let mut order = Vec::new();
+                let mut visited = std::collections::HashSet::new();
+                let mut stack = vec![(id, false)];
+                while let Some((cur, expanded)) = stack.pop() {
+                    if expanded {
+                        order.push(cur);
+                        continue;
+                    }
+                    if !visited.insert(cur) {
+                        continue;
+                    }
+                    stack.push((cur, true));
+                    for link in self.get_incoming(cur) {
+                        if !visited.contains(&link) {
+                            stack.push((link, false));
+                        }
+                    }
+                }
+                for &rid in &order {
+                    self.detach(rid);
+                }
+                Ok(order)
+            }
+        }
+    }
+
+    fn index_incoming(&mut self, id: AtomId, outgoing: &[AtomId]) {
+        for (pos, &target) in outgoing.iter().enumerate() {
             self.incoming.entry(target).or_default().push(id);
+            self.incoming_at.entry((target, pos)).or_default().push(id);
         }
-        (id, true)
+    }
+
+    /// Drop a single atom from storage and every index; its importance
+    /// goes back to the bank
+    fn detach(&mut self, id: AtomId) {
+        let Some(atom) = self.atoms.remove(&id) else {
+            return;
+        };
+        self.bank.sti += atom.av.sti;
+        self.bank.lti += atom.av.lti;
+        if let Some(ref name) = atom.name {
+            self.node_index.remove(&(atom.atom_type, name.clone()));
+        } else {
+            self.link_index
+                .remove(&link_key(atom.atom_type, &atom.outgoing));
+        }
+        if let Some(ids) = self.type_index.get_mut(&atom.atom_type) {
+            ids.retain(|&x| x != id);
+        }
+        for (pos, &target) in atom.outgoing.iter().enumerate() {
+            if let Some(links) = self.incoming.get_mut(&target) {
+                links.retain(|&x| x != id);
+                if links.is_empty() {
+                    self.incoming.remove(&target);
+                }
+            }
+            if let Some(links) = self.incoming_at.get_mut(&(target, pos)) {
+                links.retain(|&x| x != id);
+                if links.is_empty() {
+                    self.incoming_at.remove(&(target, pos));
+                }
+            }
+        }
+        // Orphaned links keep pointing here; drop the index entries for them
+        for link in self.incoming.remove(&id).unwrap_or_default() {
+            if let Some(l) = self.atoms.get(&link) {
+                for (pos, _) in l.outgoing.iter().enumerate().filter(|(_, &t)| t == id) {
+                    self.incoming_at.remove(&(id, pos));
+                }
+            }
+        }
+        self.provenance.remove(&id);
+        self.record(Mutation::Remove { id });
     }
 
     pub fn find_node(&self, atom_type: AtomType, name: &str) -> Option<AtomId> {
@@ -97,19 +604,32 @@ impl AtomSpace {
     }
 
     pub fn find_link(&self, atom_type: AtomType, outgoing: &[AtomId]) -> Option<AtomId> {
-        self.link_index
-            .get(&(atom_type, outgoing.to_vec()))
-            .copied()
+        self.link_index.get(&link_key(atom_type, outgoing)).copied()
     }
 
     pub fn get_by_type(&self, atom_type: AtomType) -> Vec<AtomId> {
         self.type_index.get(&atom_type).cloned().unwrap_or_default()
     }
 
+    /// How many atoms of `atom_type` the space holds
+    pub fn count_by_type(&self, atom_type: AtomType) -> usize {
+        self.type_index.get(&atom_type).map_or(0, Vec::len)
+    }
+
     pub fn get_incoming(&self, id: AtomId) -> Vec<AtomId> {
         self.incoming.get(&id).cloned().unwrap_or_default()
     }
 
+    /// Borrowing variant of `get_incoming` for hot loops
+    pub fn incoming_links(&self, id: AtomId) -> &[AtomId] {
+        self.incoming.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Links whose outgoing set holds `id` at index `pos`
+    pub fn incoming_at(&self, id: AtomId, pos: usize) -> &[AtomId] {
+        self.incoming_at.get(&(id, pos)).map_or(&[], Vec::as_slice)
+    }
+
     pub fn all_ids(&self) -> Vec<AtomId> {
         let mut ids: Vec<_> = self.atoms.keys().copied().collect();
         ids.sort();
@@ -122,13 +642,15 @@ impl AtomSpace {
         atoms
     }
 
-    /// Top atoms by STI, descending
+    /// Atoms holding STI, highest first (ties by id); see
+    /// `ecan::FocusBoundary` for which of them are in the focus
     pub fn atoms_by_sti(&self, limit: usize) -> Vec<&Atom> {
         let mut atoms: Vec<_> = self.atoms.values().filter(|a| a.av.sti > 0.01).collect();
         atoms.sort_by(|a, b| {
             b.av.sti
                 .partial_cmp(&a.av.sti)
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.id.cmp(&b.id))
         });
         atoms.truncate(limit);
         atoms
@@ -142,10 +664,16 @@ impl AtomSpace {
             .map(|(&t, ids)| (t, ids.len()))
             .filter(|(_, c)| *c > 0)
             .collect();
-        types.sort_by(|a, b| b.1.cmp(&a.1));
+        types.sort_by_key(|&(_, c)| std::cmp::Reverse(c));
         types
     }
 
+    /// Look up a node by bare name, preferring concepts over predicates
+    pub fn find_named(&self, name: &str) -> Option<AtomId> {
+        self.find_node(AtomType::ConceptNode, name)
+            .or_else(|| self.find_node(AtomType::PredicateNode, name))
+    }
+
     /// Human-readable name for an atom
     pub fn format_atom(&self, id: AtomId) -> String {
         let Some(atom) = self.atoms.get(&id) else {
@@ -157,9 +685,25 @@ impl AtomSpace {
             let parts: Vec<String> = atom
                 .outgoing
                 .iter()
-                .map(|&oid| self.short_name(oid))
+                .map(|&oid| self.nested_name(oid))
                 .collect();
-            format!("{}:[{}]", atom.atom_type, parts.join("\u{2192}"))
+            let arrow = if atom.atom_type.is_unordered() {
+                "\u{2194}"
+            } else {
+                "\u{2192}"
+            };
+            format!("{}:[{}]", atom.atom_type, parts.join(arrow))
+        }
+    }
+
+    /// Node name, or a nested link's members in parentheses
+    fn nested_name(&self, id: AtomId) -> String {
+        match self.atoms.get(&id) {
+            Some(a) if a.name.is_none() && !a.outgoing.is_empty() => {
+                let parts: Vec<String> = a.outgoing.iter().map(|&o| self.nested_name(o)).collect();
+                format!("({})", parts.join(","))
+            }
+            _ => self.short_name(id),
         }
     }
 
@@ -180,6 +724,39 @@ mod tests {
         TruthValue::new(s, c)
     }
 
+    #[test]
+    fn link_signatures_are_enforced() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (likes, _) = space.add_node(AtomType::PredicateNode, "likes", tv(0.9, 0.8));
+        assert_eq!(
+            space.try_add_link(AtomType::NotLink, vec![cat, cat], tv(0.9, 0.8)),
+            Err(SignatureError::Arity {
+                atom_type: AtomType::NotLink,
+                expected: Arity::Exactly(1),
+                found: 2,
+            })
+        );
+        assert!(matches!(
+            space.try_add_link(AtomType::MemberLink, vec![cat, likes], tv(0.9, 0.8)),
+            Err(SignatureError::MemberType { position: 1, .. })
+        ));
+        assert_eq!(
+            space.try_add_link(AtomType::ConceptNode, vec![cat], tv(0.9, 0.8)),
+            Err(SignatureError::NotALink(AtomType::ConceptNode))
+        );
+        assert_eq!(
+            space.try_add_link(AtomType::ListLink, vec![cat, 999], tv(0.9, 0.8)),
+            Err(SignatureError::NotFound(999))
+        );
+        assert_eq!(space.size(), 2);
+
+        let (not, _) = space.add_link(AtomType::NotLink, vec![cat], tv(0.9, 0.8));
+        let (and1, _) = space.add_link(AtomType::AndLink, vec![not, likes], tv(0.9, 0.8));
+        let (and2, _) = space.add_link(AtomType::AndLink, vec![likes, not], tv(0.9, 0.8));
+        assert_eq!(and1, and2, "AndLink is unordered");
+    }
+
     #[test]
     fn add_node_creates_atom() {
         let mut space = AtomSpace::new();
@@ -204,12 +781,72 @@ mod tests {
     #[test]
     fn node_tv_merge_keeps_higher_confidence() {
         let mut space = AtomSpace::new();
+        space.set_merge_policy(MergePolicy::KeepMax);
         let (id, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.5));
         space.add_node(AtomType::ConceptNode, "cat", tv(0.8, 0.9));
         assert!((space.get(id).unwrap().tv.confidence - 0.9).abs() < 0.01);
         assert!((space.get(id).unwrap().tv.strength - 0.8).abs() < 0.01);
     }
 
+    #[test]
+    fn revision_pools_evidence() {
+        let mut space = AtomSpace::new();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "penguin", tv(0.9, 0.8));
+        let (b, _) = space.add_node(AtomType::ConceptNode, "bird", tv(0.9, 0.8));
+        let (l, _) = space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.9, 0.5));
+        space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.9, 0.5));
+        // n = 1 + 1 = 2, c = 2/3; strength unchanged when both agree
+        let revised = space.get(l).unwrap().tv;
+        assert!((revised.confidence - 2.0 / 3.0).abs() < 1e-9);
+        assert!((revised.strength - 0.9).abs() < 1e-9);
+
+        // Disagreeing evidence: weighted by counts (n = 2 vs n = 4)
+        space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.3, 0.8));
+        let revised = space.get(l).unwrap().tv;
+        assert!((revised.strength - (2.0 * 0.9 + 4.0 * 0.3) / 6.0).abs() < 1e-9);
+        assert!((revised.confidence - 6.0 / 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn asserting_derived_atom_replaces_estimate() {
+        let mut space = AtomSpace::new();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "penguin", tv(0.9, 0.8));
+        let (b, _) = space.add_node(AtomType::ConceptNode, "flier", tv(0.9, 0.8));
+        let (l, _) = space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.9, 0.8));
+        space.set_provenance(
+            l,
+            Provenance {
+                rule: "deduction".into(),
+                premises: vec![],
+                tv: tv(0.9, 0.8),
+            },
+        );
+        space.enable_journal();
+        space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.05, 0.9));
+        assert_eq!(space.get(l).unwrap().tv, tv(0.05, 0.9), "not revised");
+        assert!(space.provenance(l).is_none());
+        assert_eq!(
+            space.take_journal(),
+            vec![
+                Mutation::Assert { id: l },
+                Mutation::SetTv {
+                    id: l,
+                    tv: tv(0.05, 0.9)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_confidence_assertion_leaves_tv_unchanged() {
+        let mut space = AtomSpace::new();
+        let (id, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.85));
+        space.add_node(AtomType::ConceptNode, "cat", TruthValue::default_tv());
+        let t = space.get(id).unwrap().tv;
+        assert!((t.strength - 0.9).abs() < 1e-9);
+        assert!((t.confidence - 0.85).abs() < 1e-9);
+    }
+
     #[test]
     fn different_types_not_deduplicated() {
         let mut space = AtomSpace::new();
@@ -231,6 +868,8 @@ mod tests {
         assert_eq!(space.get(lid).unwrap().outgoing, vec![a, b]);
         assert!(space.get_incoming(a).contains(&lid));
         assert!(space.get_incoming(b).contains(&lid));
+        assert_eq!(space.incoming_at(a, 0), &[lid]);
+        assert!(space.incoming_at(a, 1).is_empty());
     }
 
     #[test]
@@ -292,6 +931,32 @@ mod tests {
         assert_eq!(space.format_atom(a), "ConceptNode:\"cat\"");
         assert!(space.format_atom(lid).contains("cat"));
         assert!(space.format_atom(lid).contains("mammal"));
+        let (p, _) = space.add_node(AtomType::PredicateNode, "likes", tv(0.9, 0.8));
+        let (list, _) = space.add_link(AtomType::ListLink, vec![a, b], tv(0.0, 0.0));
+        let (eval, _) = space.add_link(AtomType::EvaluationLink, vec![p, list], tv(0.9, 0.8));
+        assert_eq!(
+            space.format_atom(eval),
+            "EvaluationLink:[likes\u{2192}(cat,mammal)]"
+        );
+    }
+
+    #[test]
+    fn similarity_is_one_atom_either_way() {
+        let mut space = AtomSpace::new();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (b, _) = space.add_node(AtomType::ConceptNode, "dog", tv(0.9, 0.8));
+        let (ab, new) = space.add_link(AtomType::SimilarityLink, vec![a, b], tv(0.8, 0.5));
+        assert!(new);
+        let (ba, new) = space.add_link(AtomType::SimilarityLink, vec![b, a], tv(0.8, 0.5));
+        assert_eq!((ba, new), (ab, false));
+        assert_eq!(space.find_link(AtomType::SimilarityLink, &[b, a]), Some(ab));
+        assert_eq!(space.format_atom(ab), "SimilarityLink:[cat\u{2194}dog]");
+        // Inheritance stays directed
+        let (i, _) = space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.9, 0.9));
+        assert_ne!(space.find_link(AtomType::InheritanceLink, &[b, a]), Some(i));
+
+        space.remove_atom(ab, RemovalPolicy::Refuse).unwrap();
+        assert!(space.find_link(AtomType::SimilarityLink, &[b, a]).is_none());
     }
 
     #[test]
@@ -306,4 +971,83 @@ mod tests {
         assert_eq!(top[0].id, b); // dog has higher STI
         assert_eq!(top[1].id, a);
     }
+
+    fn cat_mammal_animal() -> (AtomSpace, AtomId, AtomId, AtomId, AtomId, AtomId) {
+        let mut space = AtomSpace::new();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (b, _) = space.add_node(AtomType::ConceptNode, "mammal", tv(0.9, 0.8));
+        let (c, _) = space.add_node(AtomType::ConceptNode, "animal", tv(0.9, 0.8));
+        let (ab, _) = space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.95, 0.9));
+        let (bc, _) = space.add_link(AtomType::InheritanceLink, vec![b, c], tv(0.95, 0.9));
+        (space, a, b, c, ab, bc)
+    }
+
+    #[test]
+    fn remove_refuses_atom_with_incoming() {
+        let (mut space, _, b, _, _, _) = cat_mammal_animal();
+        let err = space.remove_atom(b, RemovalPolicy::Refuse).unwrap_err();
+        assert_eq!(err, RemoveError::HasIncoming { id: b, incoming: 2 });
+        assert_eq!(space.size(), 5);
+    }
+
+    #[test]
+    fn remove_unreferenced_link_cleans_indexes() {
+        let (mut space, a, b, _, ab, _) = cat_mammal_animal();
+        let removed = space.remove_atom(ab, RemovalPolicy::Refuse).unwrap();
+        assert_eq!(removed, vec![ab]);
+        assert!(space.get(ab).is_none());
+        assert!(space
+            .find_link(AtomType::InheritanceLink, &[a, b])
+            .is_none());
+        assert!(!space.get_incoming(a).contains(&ab));
+        assert!(!space.get_incoming(b).contains(&ab));
+        assert_eq!(space.get_by_type(AtomType::InheritanceLink).len(), 1);
+    }
+
+    #[test]
+    fn remove_cascade_drops_dependent_links() {
+        let (mut space, a, b, c, ab, bc) = cat_mammal_animal();
+        let (list, _) = space.add_link(AtomType::ListLink, vec![ab, c], tv(0.0, 0.0));
+        let removed = space.remove_atom(b, RemovalPolicy::Cascade).unwrap();
+        assert_eq!(removed.len(), 4);
+        assert_eq!(*removed.last().unwrap(), b);
+        for id in [b, ab, bc, list] {
+            assert!(space.get(id).is_none());
+        }
+        assert!(space.find_node(AtomType::ConceptNode, "mammal").is_none());
+        assert!(space.get_incoming(a).is_empty());
+        assert!(space.get_incoming(c).is_empty());
+        assert_eq!(space.size(), 2);
+    }
+
+    #[test]
+    fn remove_orphan_leaves_dangling_links() {
+        let (mut space, a, b, _, ab, _) = cat_mammal_animal();
+        let removed = space.remove_atom(b, RemovalPolicy::Orphan).unwrap();
+        assert_eq!(removed, vec![b]);
+        assert_eq!(space.get(ab).unwrap().outgoing, vec![a, b]);
+        assert!(space.get_incoming(b).is_empty());
+        // The orphaned link can still be removed cleanly afterwards
+        space.remove_atom(ab, RemovalPolicy::Refuse).unwrap();
+        assert!(space.get_incoming(a).is_empty());
+    }
+
+    #[test]
+    fn removed_node_can_be_re_added() {
+        let (mut space, a, _, _, _, _) = cat_mammal_animal();
+        space.remove_atom(a, RemovalPolicy::Cascade).unwrap();
+        let (id, is_new) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        assert!(is_new);
+        assert_ne!(id, a);
+        assert_eq!(space.get_by_type(AtomType::ConceptNode).len(), 3);
+    }
+
+    #[test]
+    fn remove_missing_atom_errors() {
+        let mut space = AtomSpace::new();
+        assert_eq!(
+            space.remove_atom(42, RemovalPolicy::Cascade),
+            Err(RemoveError::NotFound(42))
+        );
+    }
 }
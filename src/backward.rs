@@ -0,0 +1,354 @@
+//! Backward chaining — goal-directed proofs of InheritanceLinks
+//! Searches deduction chains from the source toward the target within depth
+//! and fan-out limits, and returns the best proof without adding atoms.
+//! With inversion enabled, the goal may also be proved from its stored reverse.
+
+use serde::Serialize;
+
+use crate::atom::*;
+use crate::atomspace::AtomSpace;
+use crate::explain;
+use crate::pattern;
+use crate::pln;
+use crate::rules::{Deduction, InferenceRule, Inversion, RuleRegistry};
+
+/// Search limits for `prove`
+#[derive(Debug, Clone)]
+pub struct BackwardConfig {
+    /// Maximum number of deduction steps in a chain
+    pub max_depth: u32,
+    /// Parents of each intermediate term tried, strongest first
+    pub fan_out: usize,
+    /// Also try the goal's stored reverse link by Bayes inversion; never
+    /// used inside a chain
+    pub inversion: bool,
+}
+
+impl Default for BackwardConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            fan_out: 8,
+            inversion: false,
+        }
+    }
+}
+
+impl BackwardConfig {
+    /// Default limits, inverting only if the registry enables inversion
+    pub fn for_rules(rules: &RuleRegistry) -> Self {
+        Self {
+            inversion: rules.is_enabled(Inversion.name()),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Proof {
+    pub source: AtomId,
+    pub target: AtomId,
+    pub text: String,
+    pub tv: TruthValue,
+    /// Stored link this step rests on; None for a conclusion that exists
+    /// only in the proof
+    pub link: Option<AtomId>,
+    /// Rule that chains the premises; None for a stored link
+    pub rule: Option<String>,
+    pub premises: Vec<Proof>,
+}
+
+impl Proof {
+    /// Ranking used to pick between proofs: s × c
+    pub fn score(&self) -> f64 {
+        self.tv.strength * self.tv.confidence
+    }
+
+    /// Number of stored links the proof rests on
+    pub fn leaves(&self) -> usize {
+        if self.premises.is_empty() {
+            1
+        } else {
+            self.premises.iter().map(Proof::leaves).sum()
+        }
+    }
+}
+
+/// Resolve `cat->animal` / `cat→animal` to its two ConceptNodes
+pub fn parse_goal(space: &AtomSpace, spec: &str) -> Option<(AtomId, AtomId)> {
+    let (src, tgt) = spec
+        .split_once("->")
+        .or_else(|| spec.split_once('\u{2192}'))?;
+    Some((
+        space.find_node(AtomType::ConceptNode, src.trim())?,
+        space.find_node(AtomType::ConceptNode, tgt.trim())?,
+    ))
+}
+
+/// Best proof of InheritanceLink(source, target), if one exists within the limits
+pub fn prove(
+    space: &AtomSpace,
+    source: AtomId,
+    target: AtomId,
+    config: &BackwardConfig,
+) -> Option<Proof> {
+    if source == target {
+        return None;
+    }
+    let chained = search(
+        space,
+        source,
+        target,
+        config.max_depth,
+        config,
+        &mut Vec::new(),
+    );
+    let inverted = config
+        .inversion
+        .then(|| invert(space, source, target))
+        .flatten();
+    match (chained, inverted) {
+        (Some(c), Some(i)) if i.score() > c.score() => Some(i),
+        (chained, inverted) => chained.or(inverted),
+    }
+}
+
+/// source→target from a stored target→source, if inversion admits it
+fn invert(space: &AtomSpace, source: AtomId, target: AtomId) -> Option<Proof> {
+    let rule = Inversion;
+    let reverse = space.find_link(AtomType::InheritanceLink, &[target, source])?;
+    let m = pattern::find_matches_with(space, &rule.premises(), 0, reverse).pop()?;
+    if !rule.admits(space, &m) {
+        return None;
+    }
+    Some(Proof {
+        source,
+        target,
+        text: format!(
+            "{}:[{}\u{2192}{}]",
+            AtomType::InheritanceLink,
+            space.short_name(source),
+            space.short_name(target)
+        ),
+        tv: rule.truth_value(space, &m),
+        link: space.find_link(AtomType::InheritanceLink, &[source, target]),
+        rule: Some(rule.name().to_string()),
+        premises: vec![stored(space, reverse, target, source)],
+    })
+}
+
+fn stored(space: &AtomSpace, link: AtomId, source: AtomId, target: AtomId) -> Proof {
+    Proof {
+        source,
+        target,
+        text: space.format_atom(link),
+        tv: space.get(link).map_or(TruthValue::default_tv(), |a| a.tv),
+        link: Some(link),
+        rule: None,
+        premises: Vec::new(),
+    }
+}
+
+fn search(
+    space: &AtomSpace,
+    a: AtomId,
+    c: AtomId,
+    depth: u32,
+    config: &BackwardConfig,
+    path: &mut Vec<AtomId>,
+) -> Option<Proof> {
+    let direct = space.find_link(AtomType::InheritanceLink, &[a, c]);
+    let mut best = direct.map(|id| stored(space, id, a, c));
+    if depth == 0 {
+        return best;
+    }
+
+    // Parents of `a` reachable through stored links, strongest first
+    let mut parents: Vec<(AtomId, AtomId, TruthValue)> = space
+        .get_incoming(a)
+        .into_iter()
+        .filter_map(|lid| {
+            let link = space.get(lid)?;
+            if link.atom_type != AtomType::InheritanceLink || link.outgoing.len() != 2 {
+                return None;
+            }
+            let b = link.outgoing[1];
+            (link.outgoing[0] == a && b != a && b != c && !path.contains(&b))
+                .then_some((lid, b, link.tv))
+        })
+        .collect();
+    parents.sort_by(|x, y| {
+        let sx = x.2.strength * x.2.confidence;
+        let sy = y.2.strength * y.2.confidence;
+        sy.partial_cmp(&sx).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    parents.truncate(config.fan_out);
+
+    let node_strength = |id: AtomId| space.get(id).map_or(0.0, |n| n.tv.strength);
+    path.push(a);
+    for (ab, b, tv_ab) in parents {
+        let Some(rest) = search(space, b, c, depth - 1, config, path) else {
+            continue;
+        };
+        let tv = pln::deduction_tv(
+            tv_ab,
+            rest.tv,
+            node_strength(a),
+            node_strength(b),
+            node_strength(c),
+        );
+        let candidate = Proof {
+            source: a,
+            target: c,
+            text: format!(
+                "{}:[{}\u{2192}{}]",
+                AtomType::InheritanceLink,
+                space.short_name(a),
+                space.short_name(c)
+            ),
+            tv,
+            link: direct,
+            rule: Some(Deduction.name().to_string()),
+            premises: vec![stored(space, ab, a, b), rest],
+        };
+        if best.as_ref().is_none_or(|p| candidate.score() > p.score()) {
+            best = Some(candidate);
+        }
+    }
+    path.pop();
+    best
+}
+
+/// Render a proof as indented lines, conclusion first
+pub fn render(proof: &Proof) -> Vec<String> {
+    explain::render_tree(
+        proof,
+        &|p: &Proof| match &p.rule {
+            Some(rule) => format!("\u{22a2} {} ({}) \u{2190} {}", p.text, p.tv, rule),
+            None => format!("\u{25cf} {} ({}) stored", p.text, p.tv),
+        },
+        &|p| &p.premises,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontology;
+
+    fn base() -> AtomSpace {
+        let mut s = AtomSpace::new();
+        ontology::load_base_ontology(&mut s);
+        s
+    }
+
+    #[test]
+    fn proves_chain_without_materializing() {
+        let s = base();
+        let size = s.size();
+        let (cat, lt) = parse_goal(&s, "cat->living-thing").unwrap();
+        let proof = prove(&s, cat, lt, &BackwardConfig::default()).unwrap();
+        assert_eq!(proof.rule.as_deref(), Some("deduction"));
+        assert!(proof.link.is_none());
+        // cat→mammal→animal→living-thing
+        assert_eq!(proof.leaves(), 3);
+        assert!(proof.tv.strength > 0.9);
+        assert_eq!(s.size(), size, "backward chaining adds nothing");
+    }
+
+    #[test]
+    fn stored_link_is_its_own_proof() {
+        let s = base();
+        let (cat, mammal) = parse_goal(&s, "cat\u{2192}mammal").unwrap();
+        let proof = prove(&s, cat, mammal, &BackwardConfig::default()).unwrap();
+        assert!(proof.rule.is_none());
+        assert_eq!(
+            proof.link,
+            s.find_link(AtomType::InheritanceLink, &[cat, mammal])
+        );
+        assert_eq!(render(&proof).len(), 1);
+    }
+
+    #[test]
+    fn inversion_proves_the_reverse_of_a_stored_link() {
+        let s = base();
+        let (mammal, cat) = parse_goal(&s, "mammal->cat").unwrap();
+        assert!(prove(&s, mammal, cat, &BackwardConfig::default()).is_none());
+
+        let config = BackwardConfig::for_rules(&{
+            let mut r = RuleRegistry::builtin();
+            r.set_enabled("inversion", true);
+            r
+        });
+        let proof = prove(&s, mammal, cat, &config).unwrap();
+        assert_eq!(proof.rule.as_deref(), Some("inversion"));
+        assert_eq!(
+            proof.premises[0].link,
+            s.find_link(AtomType::InheritanceLink, &[cat, mammal])
+        );
+        // Few mammals are cats: P(cat) = 1/20, P(mammal) = 3/20 in the
+        // taxonomy, so s = 0.95/3, c = 0.90·0.6
+        assert!((proof.tv.strength - 0.95 / 3.0).abs() < 1e-9);
+        assert!((proof.tv.confidence - 0.54).abs() < 1e-9);
+        // Never through a chain: dog→mammal→cat would make every sibling alike
+        let (dog, cat) = parse_goal(&s, "dog->cat").unwrap();
+        assert!(prove(&s, dog, cat, &config).is_none());
+    }
+
+    #[test]
+    fn depth_limit_bounds_chains() {
+        let s = base();
+        let (cat, thing) = parse_goal(&s, "cat->thing").unwrap();
+        let shallow = BackwardConfig {
+            max_depth: 2,
+            ..Default::default()
+        };
+        // cat→thing needs three deductions (four stored links)
+        assert!(prove(&s, cat, thing, &shallow).is_none());
+        let proof = prove(&s, cat, thing, &BackwardConfig::default()).unwrap();
+        assert_eq!(proof.leaves(), 4);
+    }
+
+    #[test]
+    fn fan_out_prunes_weak_parents() {
+        let mut s = base();
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let (pet, _) = s.add_node(AtomType::ConceptNode, "pet", TruthValue::new(0.9, 0.9));
+        let (owned, _) = s.add_node(AtomType::ConceptNode, "owned", TruthValue::new(0.9, 0.9));
+        // Weaker than cat→mammal, so tried second
+        s.add_link(
+            AtomType::InheritanceLink,
+            vec![cat, pet],
+            TruthValue::new(0.6, 0.5),
+        );
+        s.add_link(
+            AtomType::InheritanceLink,
+            vec![pet, owned],
+            TruthValue::new(0.9, 0.9),
+        );
+        let narrow = BackwardConfig {
+            fan_out: 1,
+            ..Default::default()
+        };
+        assert!(prove(&s, cat, owned, &narrow).is_none());
+        assert!(prove(&s, cat, owned, &BackwardConfig::default()).is_some());
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_proof() {
+        let s = base();
+        let (cat, plant) = parse_goal(&s, "cat->plant").unwrap();
+        assert!(prove(&s, cat, plant, &BackwardConfig::default()).is_none());
+        assert!(parse_goal(&s, "cat->unicorn").is_none());
+    }
+
+    #[test]
+    fn render_marks_chained_and_stored_steps() {
+        let s = base();
+        let (cat, animal) = parse_goal(&s, "cat->animal").unwrap();
+        let lines = render(&prove(&s, cat, animal, &BackwardConfig::default()).unwrap());
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("[cat\u{2192}animal]") && lines[0].ends_with("deduction"));
+        assert!(lines[1].ends_with("stored") && lines[2].ends_with("stored"));
+    }
+}
@@ -1,10 +1,15 @@
-use std::{env, net::SocketAddr, sync::Arc};
+use std::{
+    env,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex as StdMutex},
+};
 
 use axum::{
     extract::{Query, State},
     http::StatusCode,
     response::{Html, Json},
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
@@ -12,7 +17,18 @@ use serde_json::{json, Value};
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 
-use coggy::{atomspace::AtomSpace, cogloop, ecan::EcanConfig, ontology};
+use coggy::{
+    atomese,
+    atomspace::{AtomSpace, MergePolicy, RemovalPolicy},
+    backward::{self, BackwardConfig},
+    cogloop,
+    ecan::{EcanConfig, FocusBoundary},
+    explain, ontology,
+    pattern::{self, Pattern},
+    persist,
+    pln::InferenceControl,
+    wal::{self, Wal},
+};
 
 static INDEX_HTML: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/static/index.html"));
 
@@ -20,6 +36,20 @@ static INDEX_HTML: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/sta
 struct AppState {
     space: Arc<Mutex<AtomSpace>>,
     ecan: Arc<EcanConfig>,
+    control: Arc<InferenceControl>,
+    snapshot: Option<Arc<SnapshotConfig>>,
+    /// Atomese file that forgotten atoms are appended to
+    archive: Option<Arc<PathBuf>>,
+    // Always locked after `space`, never before
+    wal: Option<Arc<StdMutex<Wal>>>,
+    /// Focus entries and exits of the latest turn; locked after `space`
+    focus_events: Arc<StdMutex<FocusEvents>>,
+}
+
+/// Where and how often the web instance persists its AtomSpace
+struct SnapshotConfig {
+    path: PathBuf,
+    every_turns: u32,
 }
 
 #[tokio::main]
@@ -29,11 +59,101 @@ async fn main() {
         .with_max_level(tracing::Level::INFO)
         .init();
 
-    let mut base_space = AtomSpace::new();
-    ontology::load_base_ontology(&mut base_space);
+    let snapshot = env::var("COGGY_SNAPSHOT").ok().map(|path| SnapshotConfig {
+        path: PathBuf::from(path),
+        every_turns: env::var("COGGY_SNAPSHOT_EVERY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(10),
+    });
+
+    // The journal lives next to the snapshot unless COGGY_WAL says otherwise
+    let wal_path = env::var("COGGY_WAL")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| snapshot.as_ref().map(|s| s.path.with_extension("wal")));
+
+    let mut base_space = match snapshot.as_ref().filter(|s| s.path.exists()) {
+        Some(cfg) => {
+            let space = persist::load_snapshot(&cfg.path).expect("load AtomSpace snapshot");
+            tracing::info!(
+                "Restored {} atoms (turn {}) from {}",
+                space.size(),
+                space.turn,
+                cfg.path.display()
+            );
+            space
+        }
+        None => {
+            let mut space = AtomSpace::new();
+            ontology::load_base_ontology(&mut space);
+            space
+        }
+    };
+    if let Ok(name) = env::var("COGGY_MERGE") {
+        let policy = MergePolicy::from_name(&name)
+            .unwrap_or_else(|| panic!("unknown COGGY_MERGE '{}' (revision or keep-max)", name));
+        base_space.set_merge_policy(policy);
+    }
+    let mut control = InferenceControl::default();
+    if let Ok(names) = env::var("COGGY_RULES") {
+        let names: Vec<&str> = names.split(',').map(str::trim).collect();
+        if let Err(name) = control.rules.enable_only(&names) {
+            panic!(
+                "unknown rule '{}' in COGGY_RULES ({})",
+                name,
+                control.rules.names().join(", ")
+            );
+        }
+    }
+    let mut ecan = EcanConfig::default();
+    if let Ok(max) = env::var("COGGY_MAX_ATOMS") {
+        let max = max
+            .parse()
+            .unwrap_or_else(|_| panic!("COGGY_MAX_ATOMS must be a number, got '{}'", max));
+        ecan.max_atoms = Some(max);
+    }
+    if let Ok(spec) = env::var("COGGY_FOCUS") {
+        ecan.focus = FocusBoundary::from_name(&spec).unwrap_or_else(|| {
+            panic!(
+                "COGGY_FOCUS must be an STI, top:<k> or p<percentile>, got '{}'",
+                spec
+            )
+        });
+    }
+    let wal = wal_path.map(|path| {
+        if path.exists() {
+            let report = wal::replay(&mut base_space, &path).expect("replay AtomSpace journal");
+            if report.truncated > 0 {
+                tracing::warn!(
+                    "Cut a torn {}-byte record from the end of {}",
+                    report.truncated,
+                    path.display()
+                );
+            }
+            tracing::info!(
+                "Replayed {} journal entries ({} already in snapshot) from {}",
+                report.applied,
+                report.skipped,
+                path.display()
+            );
+        }
+        base_space.enable_journal();
+        Arc::new(StdMutex::new(
+            Wal::open(&path).expect("open AtomSpace journal"),
+        ))
+    });
     let state = AppState {
         space: Arc::new(Mutex::new(base_space)),
-        ecan: Arc::new(EcanConfig::default()),
+        ecan: Arc::new(ecan),
+        control: Arc::new(control),
+        snapshot: snapshot.map(Arc::new),
+        archive: env::var("COGGY_ARCHIVE")
+            .ok()
+            .map(|p| Arc::new(PathBuf::from(p))),
+        wal,
+        focus_events: Arc::new(StdMutex::new(FocusEvents::default())),
     };
 
     let port = env::var("COGGY_PORT")
@@ -48,7 +168,12 @@ async fn main() {
         .route("/api/feed", get(feed))
         .route("/api/trace", get(trace))
         .route("/api/focus", get(focus))
-        .with_state(state);
+        .route("/api/remove", post(remove))
+        .route("/api/atomese", get(export_atomese))
+        .route("/api/query", get(query))
+        .route("/api/explain", get(explain_atom))
+        .route("/api/prove", get(prove))
+        .with_state(state.clone());
 
     tracing::info!("Serving Coggy web experience on http://{}", addr);
 
@@ -56,7 +181,67 @@ async fn main() {
         .await
         .expect("bind to shipping listener");
 
-    axum::serve(listener, app).await.expect("web server to run");
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await
+        .expect("web server to run");
+
+    let mut space = state.space.lock().await;
+    persist_changes(&state, &mut space, true);
+}
+
+/// Flush journaled mutations to the WAL and, on checkpoints, fold them into
+/// the snapshot. Failures are logged, never fatal, so a full disk does not
+/// take the API down.
+fn persist_changes(state: &AppState, space: &mut AtomSpace, checkpoint: bool) {
+    let entries = space.take_journal();
+    let snapshot = state.snapshot.as_deref().filter(|_| checkpoint);
+    if let Some(wal) = state.wal.as_deref() {
+        let mut wal = wal.lock().expect("journal lock");
+        let mut appended = true;
+        if let Err(e) = wal.append(&entries) {
+            tracing::error!("Journal append to {} failed: {}", wal.path().display(), e);
+            // Keep them for the next append, and fold them into a snapshot
+            // now if there is one, rather than leave a gap in the journal
+            space.restore_journal(entries);
+            appended = false;
+        }
+        let compact_into = state
+            .snapshot
+            .as_deref()
+            .filter(|_| checkpoint || !appended);
+        if let Some(cfg) = compact_into {
+            match wal.compact(space, &cfg.path) {
+                Ok(()) => {
+                    if !appended {
+                        space.take_journal();
+                    }
+                    tracing::info!(
+                        "Compacted journal into {} ({} atoms, turn {})",
+                        cfg.path.display(),
+                        space.size(),
+                        space.turn
+                    )
+                }
+                Err(e) => tracing::error!("Compaction into {} failed: {}", cfg.path.display(), e),
+            }
+            return;
+        }
+    }
+    let Some(cfg) = snapshot else {
+        return;
+    };
+    match persist::save_snapshot(space, &cfg.path) {
+        Ok(()) => tracing::info!(
+            "Saved {} atoms (turn {}) to {}",
+            space.size(),
+            space.turn,
+            cfg.path.display()
+        ),
+        Err(e) => tracing::error!("Snapshot to {} failed: {}", cfg.path.display(), e),
+    }
 }
 
 async fn root() -> Html<&'static str> {
@@ -74,8 +259,10 @@ async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
 
 async fn focus(State(state): State<AppState>) -> Json<FocusResponse> {
     let space = state.space.lock().await;
-    let tops: Vec<_> = space
-        .atoms_by_sti(10)
+    let tops: Vec<_> = state
+        .ecan
+        .focus
+        .select(&space)
         .into_iter()
         .map(|atom| FocusEntry {
             atom: space.format_atom(atom.id),
@@ -87,16 +274,25 @@ async fn focus(State(state): State<AppState>) -> Json<FocusResponse> {
             },
         })
         .collect();
+    let events = state
+        .focus_events
+        .lock()
+        .expect("focus events lock")
+        .clone();
     Json(FocusResponse {
         turn: space.turn,
+        boundary: state.ecan.focus.to_string(),
         focus: tops,
+        shift: events,
     })
 }
 
 async fn feed(State(state): State<AppState>) -> Json<FeedResponse> {
     let space = state.space.lock().await;
-    let focus_items: Vec<_> = space
-        .atoms_by_sti(8)
+    let focus_items: Vec<_> = state
+        .ecan
+        .focus
+        .select(&space)
         .into_iter()
         .map(|atom| FocusEntry {
             atom: space.format_atom(atom.id),
@@ -133,14 +329,51 @@ async fn trace(
     }
 
     let mut space = state.space.lock().await;
-    let result = cogloop::run(&mut space, input, &state.ecan);
+    let result = cogloop::run_with(&mut space, input, &state.ecan, &state.control);
+    if let Some(path) = state.archive.as_deref() {
+        if let Err(e) = persist::append_archive(path, &result.forgotten) {
+            tracing::error!("Archive to {} failed: {}", path.display(), e);
+        }
+    }
+    let checkpoint = state
+        .snapshot
+        .as_deref()
+        .is_some_and(|cfg| result.turn.is_multiple_of(cfg.every_turns));
+    persist_changes(&state, &mut space, checkpoint);
+    let names = |ids: &[coggy::atom::AtomId]| -> Vec<String> {
+        ids.iter().map(|&id| space.format_atom(id)).collect()
+    };
+    let events = FocusEvents {
+        turn: result.turn,
+        entered: names(&result.focus_shift.entered),
+        left: names(&result.focus_shift.left),
+    };
+    *state.focus_events.lock().expect("focus events lock") = events.clone();
     let trace: Vec<_> = result
         .trace
         .iter()
         .map(|step| json!({ "phase": step.phase, "lines": step.lines }))
         .collect();
-    let focus: Vec<_> = space
-        .atoms_by_sti(6)
+    let contradictions: Vec<Value> = result
+        .contradictions
+        .iter()
+        .map(|c| {
+            let premises: Vec<String> =
+                c.premises.iter().map(|&id| space.format_atom(id)).collect();
+            json!({
+                "atom": space.format_atom(c.atom),
+                "stored": { "s": c.stored.strength, "c": c.stored.confidence },
+                "rule": c.rule,
+                "premises": premises,
+                "inferred": { "s": c.inferred.strength, "c": c.inferred.confidence },
+            })
+        })
+        .collect();
+
+    let focus: Vec<_> = state
+        .ecan
+        .focus
+        .select(&space)
         .into_iter()
         .map(|atom| {
             json!({
@@ -162,6 +395,120 @@ async fn trace(
         "inferences": result.inferences,
         "trace": trace,
         "focus": focus,
+        "focus_shift": { "entered": events.entered, "left": events.left },
+        "answer": result.answer,
+        "contradictions": contradictions,
+        "forgotten": result.forgotten.iter().map(|f| &f.desc).collect::<Vec<_>>(),
+        "bank": space.bank(),
+    })))
+}
+
+async fn query(
+    Query(params): Query<PatternQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let pattern =
+        Pattern::parse(&params.pattern).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let space = state.space.lock().await;
+    let matches: Vec<_> = pattern::find_matches(&space, &pattern)
+        .into_iter()
+        .map(|m| {
+            m.bindings
+                .iter()
+                .map(|(var, &id)| (var.clone(), json!(space.format_atom(id))))
+                .collect::<serde_json::Map<_, _>>()
+        })
+        .collect();
+    Ok(Json(json!({
+        "event": "query",
+        "count": matches.len(),
+        "matches": matches,
+    })))
+}
+
+async fn explain_atom(
+    Query(params): Query<ExplainQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let space = state.space.lock().await;
+    let proof = explain::resolve(&space, &params.atom)
+        .and_then(|id| explain::explain(&space, id))
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("no atom '{}' (use a name or src->tgt)", params.atom),
+            )
+        })?;
+    Ok(Json(json!({
+        "event": "explain",
+        "steps": proof.steps(),
+        "lines": explain::render(&proof),
+        "proof": proof,
+    })))
+}
+
+async fn prove(
+    Query(params): Query<ProveQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let space = state.space.lock().await;
+    let (source, target) = backward::parse_goal(&space, &params.goal).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("cannot resolve goal '{}' (use src->tgt)", params.goal),
+        )
+    })?;
+    let defaults = BackwardConfig::for_rules(&state.control.rules);
+    let config = BackwardConfig {
+        max_depth: params.depth.unwrap_or(defaults.max_depth).min(8),
+        fan_out: params.fan_out.unwrap_or(defaults.fan_out).min(32),
+        inversion: defaults.inversion,
+    };
+    let proof = backward::prove(&space, source, target, &config);
+    Ok(Json(json!({
+        "event": "prove",
+        "proved": proof.is_some(),
+        "lines": proof.as_ref().map(backward::render).unwrap_or_default(),
+        "proof": proof,
+    })))
+}
+
+async fn export_atomese(State(state): State<AppState>) -> String {
+    let space = state.space.lock().await;
+    atomese::export(&space)
+}
+
+async fn remove(
+    Query(params): Query<RemoveQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let policy = match params.policy.as_deref() {
+        None | Some("refuse") => RemovalPolicy::Refuse,
+        Some("cascade") => RemovalPolicy::Cascade,
+        Some("orphan") => RemovalPolicy::Orphan,
+        Some(other) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("unknown removal policy '{}'", other),
+            ))
+        }
+    };
+
+    let mut space = state.space.lock().await;
+    let id = space.find_named(params.atom.trim()).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("no atom named '{}'", params.atom),
+        )
+    })?;
+    let removed = space
+        .remove_atom(id, policy)
+        .map_err(|e| (StatusCode::CONFLICT, e.to_string()))?;
+    persist_changes(&state, &mut space, false);
+    Ok(Json(json!({
+        "event": "remove",
+        "removed": removed,
+        "total_atoms": space.size(),
     })))
 }
 
@@ -175,7 +522,17 @@ struct HealthResponse {
 #[derive(Serialize)]
 struct FocusResponse {
     turn: u32,
+    boundary: String,
     focus: Vec<FocusEntry>,
+    /// What entered and left the focus on the latest cognitive-loop turn
+    shift: FocusEvents,
+}
+
+#[derive(Clone, Default, Serialize)]
+struct FocusEvents {
+    turn: u32,
+    entered: Vec<String>,
+    left: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -209,3 +566,26 @@ struct TruthValueEntry {
 struct TraceQuery {
     input: String,
 }
+
+#[derive(Deserialize)]
+struct RemoveQuery {
+    atom: String,
+    policy: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProveQuery {
+    goal: String,
+    depth: Option<u32>,
+    fan_out: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ExplainQuery {
+    atom: String,
+}
+
+#[derive(Deserialize)]
+struct PatternQuery {
+    pattern: String,
+}
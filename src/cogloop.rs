@@ -1,9 +1,19 @@
 //! The cognitive loop: PARSE → GROUND → ATTEND → INFER → REFLECT
+//! Questions take PARSE → ANSWER → REFLECT and leave the atoms untouched.
+//! A CONTRADICT step follows INFER when the turn's links conflict with what
+//! the rules conclude from the rest of the space, and a FORGET step when the
+//! space has outgrown `EcanConfig::max_atoms`.
 
+use std::collections::HashSet;
+
+use crate::answer::{self, Answer};
+use crate::atom::{AtomId, AtomType};
 use crate::atomspace::AtomSpace;
-use crate::ecan::{self, EcanConfig};
+use crate::backward::{self, BackwardConfig};
+use crate::ecan::{self, EcanConfig, FocusShift, Forgotten};
+use crate::ontology;
 use crate::parse;
-use crate::pln;
+use crate::pln::{self, Contradiction, InferenceControl};
 
 pub struct TraceStep {
     pub phase: String,
@@ -16,12 +26,30 @@ pub struct CogLoopResult {
     pub turn: u32,
     pub inferences: usize,
     pub trace: Vec<TraceStep>,
+    pub answer: Option<Answer>,
+    /// Stored atoms the rules disagree with, found around this turn's links
+    pub contradictions: Vec<Contradiction>,
+    /// Atoms the forgetting agent removed this turn
+    pub forgotten: Vec<Forgotten>,
+    /// Atoms that entered and left the attentional focus during ATTEND
+    pub focus_shift: FocusShift,
 }
 
 pub fn run(space: &mut AtomSpace, input: &str, ecan_config: &EcanConfig) -> CogLoopResult {
-    space.turn += 1;
-    let turn = space.turn;
-    let initial_size = space.size();
+    run_with(space, input, ecan_config, &InferenceControl::default())
+}
+
+/// `run` with explicit attention-guided inference limits for the INFER phase
+pub fn run_with(
+    space: &mut AtomSpace,
+    input: &str,
+    ecan_config: &EcanConfig,
+    control: &InferenceControl,
+) -> CogLoopResult {
+    let turn = space.begin_turn(input);
+    // Ids only grow, so everything from here on was created this turn, even
+    // if ATTEND removes faded Hebbian links and the space shrinks
+    let first_new = space.next_id();
     let mut trace = Vec::new();
 
     // ── PARSE ──────────────────────────────────────────────
@@ -36,6 +64,11 @@ pub fn run(space: &mut AtomSpace, input: &str, ecan_config: &EcanConfig) -> CogL
         lines: parse_lines,
     });
 
+    if let Some(question) = &parsed.question {
+        let config = BackwardConfig::for_rules(&control.rules);
+        return answer_question(space, question, &config, turn, trace);
+    }
+
     // ── GROUND ─────────────────────────────────────────────
     let mut ground_lines = Vec::new();
     for pa in &parsed.atoms {
@@ -72,7 +105,23 @@ pub fn run(space: &mut AtomSpace, input: &str, ecan_config: &EcanConfig) -> CogL
     // ── ATTEND ─────────────────────────────────────────────
     // Boost all referenced atoms (not just new ones) — they were mentioned
     let activated = parsed.all_ids();
+    let hebbian = |space: &AtomSpace| {
+        let mut ids = space.get_by_type(AtomType::AsymmetricHebbianLink);
+        ids.extend(space.get_by_type(AtomType::HebbianLink));
+        ids
+    };
+    let known_hebbian: HashSet<AtomId> = hebbian(space).into_iter().collect();
+    let focus_before = ecan_config.focus.ids(space);
     let sti_changes = ecan::spread_attention(space, &activated, ecan_config);
+    let focus_shift = FocusShift::between(&focus_before, &ecan_config.focus.ids(space));
+    let faded = known_hebbian
+        .iter()
+        .filter(|&&id| space.get(id).is_none())
+        .count();
+    let learned: Vec<AtomId> = hebbian(space)
+        .into_iter()
+        .filter(|id| !known_hebbian.contains(id))
+        .collect();
 
     let mut attend_lines = Vec::new();
     let mut sorted: Vec<_> = sti_changes
@@ -104,13 +153,43 @@ pub fn run(space: &mut AtomSpace, input: &str, ecan_config: &EcanConfig) -> CogL
     if attend_lines.is_empty() {
         attend_lines.push("no attention changes".into());
     }
+    for (ids, verb) in [
+        (&focus_shift.entered, "\u{25b2} enters focus"),
+        (&focus_shift.left, "\u{25bc} leaves focus"),
+    ] {
+        for &id in ids.iter().take(6) {
+            attend_lines.push(format!("{}: {}", verb, space.format_atom(id)));
+        }
+        if ids.len() > 6 {
+            attend_lines.push(format!("{}: ... {} more", verb, ids.len() - 6));
+        }
+    }
+    for &id in learned.iter().take(6) {
+        attend_lines.push(format!("\u{2194} learned {}", space.format_atom(id)));
+    }
+    if learned.len() > 6 {
+        attend_lines.push(format!(
+            "\u{2194} ... {} more Hebbian links",
+            learned.len() - 6
+        ));
+    }
+    if faded > 0 {
+        attend_lines.push(format!("\u{2194} {} faded Hebbian links removed", faded));
+    }
+    attend_lines.push(format!(
+        "\u{25c7} bank: {:.1} STI, {:.1} LTI unspent",
+        space.bank().sti,
+        space.bank().lti
+    ));
     trace.push(TraceStep {
         phase: "ATTEND \u{2192} STI spread".into(),
         lines: attend_lines,
     });
 
     // ── INFER ──────────────────────────────────────────────
-    let inferences = pln::forward_chain(space, 2);
+    // What changed since the last pass, what the user is talking about first
+    let focus = ecan_config.focus.ids(space);
+    let inferences = pln::forward_chain_focused(space, 2, control, &focus);
     let inf_count = inferences.len();
 
     let mut infer_lines = Vec::new();
@@ -131,14 +210,73 @@ pub fn run(space: &mut AtomSpace, input: &str, ecan_config: &EcanConfig) -> CogL
     }
     trace.push(TraceStep {
         phase: format!(
-            "INFER \u{2192} PLN forward chain (depth 2) \u{2014} {} inferences",
+            "INFER \u{2192} PLN forward chain (depth 2, focus {}, budget {}) \u{2014} {} inferences",
+            focus.len(),
+            control.budget,
             inf_count
         ),
         lines: infer_lines,
     });
 
+    // ── CONTRADICT ─────────────────────────────────────────
+    // Check what the user just said against what the rules conclude
+    let rules = control.rules.enabled();
+    let mut contradictions: Vec<Contradiction> = Vec::new();
+    for pa in &parsed.atoms {
+        if space.get(pa.id).is_some_and(|a| a.atom_type.is_node()) {
+            continue;
+        }
+        for c in pln::contradictions(space, pa.id, &rules) {
+            if !contradictions
+                .iter()
+                .any(|k| k.atom == c.atom && k.premises == c.premises)
+            {
+                contradictions.push(c);
+            }
+        }
+    }
+    if !contradictions.is_empty() {
+        let lines = contradictions
+            .iter()
+            .map(|c| {
+                let premises: Vec<String> =
+                    c.premises.iter().map(|&id| space.format_atom(id)).collect();
+                format!(
+                    "\u{26a0} {} is ({}) but {} [{}] gives ({})",
+                    space.format_atom(c.atom),
+                    c.stored,
+                    c.rule,
+                    premises.join(", "),
+                    c.inferred
+                )
+            })
+            .collect();
+        trace.push(TraceStep {
+            phase: format!(
+                "CONTRADICT \u{2192} asserted vs inferred \u{2014} {} conflicts",
+                contradictions.len()
+            ),
+            lines,
+        });
+    }
+
+    // ── FORGET ─────────────────────────────────────────────
+    let new_count = (space.next_id() - first_new) as usize;
+    let forgotten = ecan::forget(space, ecan_config, &ontology::base_atoms(space));
+    if !forgotten.is_empty() {
+        trace.push(TraceStep {
+            phase: format!(
+                "FORGET \u{2192} low STI and LTI \u{2014} {} atoms removed",
+                forgotten.len()
+            ),
+            lines: forgotten
+                .iter()
+                .map(|f| format!("\u{2717} {}", f.desc))
+                .collect(),
+        });
+    }
+
     // ── REFLECT ────────────────────────────────────────────
-    let new_count = space.size() - initial_size;
     let top = space.atoms_by_sti(1);
     let peak = if let Some(a) = top.first() {
         format!(
@@ -150,9 +288,19 @@ pub fn run(space: &mut AtomSpace, input: &str, ecan_config: &EcanConfig) -> CogL
         String::new()
     };
 
+    let conflicts = if contradictions.is_empty() {
+        String::new()
+    } else {
+        format!("  |  Contradictions: {}", contradictions.len())
+    };
+    let forgot = if forgotten.is_empty() {
+        String::new()
+    } else {
+        format!("  |  Forgotten: {}", forgotten.len())
+    };
     let reflect_lines = vec![format!(
-        "New atoms: {}  |  Inferred: {}{}",
-        new_count, inf_count, peak
+        "New atoms: {}  |  Inferred: {}{}{}{}",
+        new_count, inf_count, conflicts, forgot, peak
     )];
     trace.push(TraceStep {
         phase: "REFLECT \u{2192} trace summary".into(),
@@ -165,5 +313,289 @@ pub fn run(space: &mut AtomSpace, input: &str, ecan_config: &EcanConfig) -> CogL
         turn,
         inferences: inf_count,
         trace,
+        answer: None,
+        contradictions,
+        forgotten,
+        focus_shift,
+    }
+}
+
+/// ANSWER and REFLECT for a question turn; nothing is grounded, attended
+/// or inferred, so the space is left exactly as it was
+fn answer_question(
+    space: &AtomSpace,
+    question: &parse::Question,
+    config: &BackwardConfig,
+    turn: u32,
+    mut trace: Vec<TraceStep>,
+) -> CogLoopResult {
+    let answer = answer::answer_with(space, question, config);
+
+    let mut answer_lines = Vec::new();
+    if answer.subject.is_none() {
+        answer_lines.push(format!(
+            "\u{25cb} nothing known \u{2014} {}",
+            answer.question
+        ));
+    } else if answer.facts.is_empty() {
+        answer_lines.push(format!("\u{25cb} no facts \u{2014} {}", answer.question));
+    }
+    for f in answer.facts.iter().take(12) {
+        answer_lines.push(format!(
+            "\u{25c6} {} ({}) STI {:.1} \u{2014} score {:.2}",
+            f.text, f.tv, f.sti, f.score
+        ));
+    }
+    if let Some(proof) = &answer.proof {
+        answer_lines.extend(
+            backward::render(proof)
+                .into_iter()
+                .map(|l| format!("  {}", l)),
+        );
+    }
+    trace.push(TraceStep {
+        phase: format!(
+            "ANSWER \u{2192} {} \u{2014} {} facts",
+            answer.question,
+            answer.facts.len()
+        ),
+        lines: answer_lines,
+    });
+
+    let best = answer
+        .facts
+        .first()
+        .map(|f| format!("  |  Best: {}", f.text))
+        .unwrap_or_default();
+    trace.push(TraceStep {
+        phase: "REFLECT \u{2192} trace summary".into(),
+        lines: vec![format!(
+            "Question answered, space unchanged  |  Facts: {}{}",
+            answer.facts.len(),
+            best
+        )],
+    });
+
+    CogLoopResult {
+        new_atoms: 0,
+        total_atoms: space.size(),
+        turn,
+        inferences: 0,
+        trace,
+        answer: Some(answer),
+        contradictions: Vec::new(),
+        forgotten: Vec::new(),
+        focus_shift: FocusShift::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn question_answers_without_mutating() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let config = EcanConfig::default();
+        run(&mut space, "cat likes fish", &config);
+        let before: Vec<_> = space
+            .all_atoms_sorted()
+            .iter()
+            .map(|a| (a.id, a.tv, a.av.sti))
+            .collect();
+
+        let result = run(&mut space, "what is cat?", &config);
+        let after: Vec<_> = space
+            .all_atoms_sorted()
+            .iter()
+            .map(|a| (a.id, a.tv, a.av.sti))
+            .collect();
+        assert_eq!(before, after);
+        assert!(space
+            .find_node(crate::atom::AtomType::ConceptNode, "what")
+            .is_none());
+
+        assert_eq!(result.new_atoms, 0);
+        assert!(result.trace.iter().any(|t| t.phase.starts_with("ANSWER")));
+        assert!(!result.trace.iter().any(|t| t.phase.starts_with("INFER")));
+        let answer = result.answer.expect("structured answer");
+        assert!(answer.facts.iter().any(|f| f.text == "cat likes fish"));
+    }
+
+    #[test]
+    fn inference_starts_on_topic() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let result = run(&mut space, "cat is-a pet", &EcanConfig::default());
+        assert!(result.inferences > 0);
+        assert!(result.inferences <= InferenceControl::default().budget);
+        // What the user said is chained before the rest of the new links
+        let lines = &result
+            .trace
+            .iter()
+            .find(|t| t.phase.starts_with("INFER"))
+            .unwrap()
+            .lines;
+        let on_topic = lines.iter().take_while(|l| l.contains("cat")).count();
+        assert!(on_topic > 0);
+        assert!(lines[on_topic..].iter().all(|l| !l.contains("cat")));
+    }
+
+    #[test]
+    fn statements_carry_no_answer() {
+        let mut space = AtomSpace::new();
+        let result = run(&mut space, "cat is-a pet", &EcanConfig::default());
+        assert!(result.answer.is_none());
+        assert!(!result.trace.iter().any(|t| t.phase.starts_with("ANSWER")));
+    }
+
+    #[test]
+    fn negation_against_inference_is_traced() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let config = EcanConfig::default();
+        run(&mut space, "bird is-a flier", &config);
+        let r = run(&mut space, "eagle is not a flier", &config);
+        assert_eq!(r.contradictions.len(), 1);
+        let c = &r.contradictions[0];
+        assert!(c.stored.strength < 0.1 && c.inferred.strength > 0.9);
+        let step = r
+            .trace
+            .iter()
+            .find(|t| t.phase.starts_with("CONTRADICT"))
+            .expect("contradiction step");
+        assert!(step.lines[0].contains("eagle\u{2192}flier"));
+        assert!(r.trace.last().unwrap().lines[0].contains("Contradictions: 1"));
+    }
+
+    #[test]
+    fn forgetting_bounds_the_space_and_spares_the_ontology() {
+        let mut space = AtomSpace::new();
+        let loaded = ontology::load_base_ontology(&mut space);
+        let config = EcanConfig {
+            max_atoms: Some(loaded + 20),
+            // Count parsed atoms only
+            hebbian_max_focus: 0,
+            ..EcanConfig::default()
+        };
+        let quiet = InferenceControl {
+            budget: 0,
+            ..InferenceControl::default()
+        };
+        let mut forgot = 0;
+        for i in 0..20 {
+            let r = run_with(
+                &mut space,
+                &format!("ant{} moves{} crumb{}", i, i, i),
+                &config,
+                &quiet,
+            );
+            forgot += r.forgotten.len();
+        }
+        assert!(forgot > 0);
+        assert_eq!(space.size() + forgot, loaded + 100, "5 atoms per turn");
+        assert_eq!(ontology::base_atoms(&space).len(), loaded);
+    }
+
+    #[test]
+    fn long_varied_conversation_counts_new_atoms() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let config = EcanConfig::default();
+        let inputs = [
+            "cat likes fish",
+            "dog likes bone",
+            "bird eats seed",
+            "cow eats grass",
+            "fish needs water",
+            "tree needs sun",
+            "cat is a pet",
+        ];
+        for turn in 0..60 {
+            let before = space.next_id();
+            let r = run(&mut space, inputs[turn % inputs.len()], &config);
+            assert_eq!(r.new_atoms as u64, space.next_id() - before);
+            assert_eq!(r.total_atoms, space.size());
+        }
+    }
+
+    #[test]
+    fn unmentioned_pairs_leave_the_focus_and_their_link_fades() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let config = EcanConfig::default();
+        for _ in 0..3 {
+            run(&mut space, "cat likes fish", &config);
+        }
+        let cat = space.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let fish = space.find_node(AtomType::ConceptNode, "fish").unwrap();
+        let link = space
+            .find_link(AtomType::HebbianLink, &[cat, fish])
+            .unwrap();
+        let learned = space.get(link).unwrap().tv.strength;
+        for _ in 0..57 {
+            run(&mut space, "dog likes bone", &config);
+        }
+        let focus = config.focus.ids(&space);
+        assert!(!focus.contains(&cat) && !focus.contains(&fish));
+        assert!(space
+            .get(link)
+            .is_none_or(|l| l.tv.strength < learned - 0.5));
+    }
+
+    #[test]
+    fn shared_focus_teaches_hebbian_links() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let r = run(&mut space, "cat likes fish", &EcanConfig::default());
+        let step = r
+            .trace
+            .iter()
+            .find(|t| t.phase.starts_with("ATTEND"))
+            .unwrap();
+        assert!(step
+            .lines
+            .iter()
+            .any(|l| l.contains("learned HebbianLink") && l.contains("fish")));
+        let cat = space.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let fish = space.find_node(AtomType::ConceptNode, "fish").unwrap();
+        let link = space
+            .find_link(AtomType::HebbianLink, &[cat, fish])
+            .expect("no link was asserted, but cat and fish were in focus together");
+        let first = space.get(link).unwrap().tv;
+        run(&mut space, "cat likes fish", &EcanConfig::default());
+        assert!(space.get(link).unwrap().tv.confidence > first.confidence);
+    }
+
+    #[test]
+    fn attend_reports_focus_entries_and_exits() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let config = EcanConfig {
+            focus: ecan::FocusBoundary::TopK(2),
+            hebbian_max_focus: 0,
+            ..EcanConfig::default()
+        };
+        let r = run(&mut space, "cat is-a pet", &config);
+        let cat = space.find_node(AtomType::ConceptNode, "cat").unwrap();
+        assert_eq!(r.focus_shift.entered.len(), 2);
+        assert!(r.focus_shift.entered.contains(&cat) && r.focus_shift.left.is_empty());
+
+        let r = run(&mut space, "dog is-a pet", &config);
+        assert!(r.focus_shift.left.contains(&cat));
+        let attend = r
+            .trace
+            .iter()
+            .find(|t| t.phase.starts_with("ATTEND"))
+            .unwrap();
+        assert!(attend
+            .lines
+            .iter()
+            .any(|l| l.starts_with("\u{25bc} leaves focus") && l.contains("\"cat\"")));
+        assert!(attend
+            .lines
+            .iter()
+            .any(|l| l.starts_with("\u{25b2} enters focus") && l.contains("\"dog\"")));
     }
 }
@@ -1,28 +1,209 @@
 //! ECAN — Economic Attention Network
-//! Spreads short-term importance (STI) through the hypergraph.
+//! Pays short-term importance (STI) from the AtomSpace's attention bank,
+//! spreads it through the hypergraph, accrues long-term importance (LTI) to
+//! atoms that keep entering the focus, learns Hebbian links between atoms
+//! that share the focus, and forgets unimportant atoms once the space grows
+//! past its limit.
 
-use crate::atom::AtomId;
-use crate::atomspace::AtomSpace;
+use crate::atom::{Atom, AtomId, AtomType, TruthValue};
+use crate::atomese;
+use crate::atomspace::{AtomSpace, RemovalPolicy};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Where the attentional focus ends. Only atoms holding some STI (> 0.01)
+/// are ever in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FocusBoundary {
+    /// Atoms at or above this STI
+    Absolute(f64),
+    /// The K atoms with the highest STI
+    TopK(usize),
+    /// Atoms at or above this percentile (0–100) of the STI held
+    Percentile(f64),
+}
+
+impl FocusBoundary {
+    /// Parse `"10"` (absolute STI), `"top:12"` or `"p90"`
+    pub fn from_name(name: &str) -> Option<FocusBoundary> {
+        if let Some(k) = name.strip_prefix("top:") {
+            return k.parse().ok().map(FocusBoundary::TopK);
+        }
+        if let Some(p) = name.strip_prefix('p') {
+            return p
+                .parse()
+                .ok()
+                .filter(|p| (0.0..=100.0).contains(p))
+                .map(FocusBoundary::Percentile);
+        }
+        name.parse()
+            .ok()
+            .filter(|t: &f64| t.is_finite())
+            .map(FocusBoundary::Absolute)
+    }
+
+    /// Atoms inside the boundary, highest STI first
+    pub fn select<'a>(&self, space: &'a AtomSpace) -> Vec<&'a Atom> {
+        let mut atoms = space.atoms_by_sti(usize::MAX);
+        match *self {
+            FocusBoundary::Absolute(min) => atoms.retain(|a| a.av.sti >= min),
+            FocusBoundary::TopK(k) => atoms.truncate(k),
+            FocusBoundary::Percentile(p) => {
+                // Nearest rank; atoms tied with the last one stay in
+                let keep = (atoms.len() as f64 * (100.0 - p) / 100.0).ceil() as usize;
+                let min = match keep.min(atoms.len()) {
+                    0 => f64::INFINITY,
+                    n => atoms[n - 1].av.sti,
+                };
+                atoms.retain(|a| a.av.sti >= min);
+            }
+        }
+        atoms
+    }
+
+    /// Ids of the atoms in focus, highest STI first
+    pub fn ids(&self, space: &AtomSpace) -> Vec<AtomId> {
+        self.select(space).into_iter().map(|a| a.id).collect()
+    }
+}
+
+impl fmt::Display for FocusBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FocusBoundary::Absolute(min) => write!(f, "STI \u{2265} {}", min),
+            FocusBoundary::TopK(k) => write!(f, "top {}", k),
+            FocusBoundary::Percentile(p) => write!(f, "{}th percentile", p),
+        }
+    }
+}
+
+/// Atoms that entered and left the focus between two readings of it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FocusShift {
+    pub entered: Vec<AtomId>,
+    pub left: Vec<AtomId>,
+}
+
+impl FocusShift {
+    /// Compare two `FocusBoundary::ids` readings; order follows each reading
+    pub fn between(before: &[AtomId], after: &[AtomId]) -> FocusShift {
+        let was: HashSet<AtomId> = before.iter().copied().collect();
+        let is: HashSet<AtomId> = after.iter().copied().collect();
+        FocusShift {
+            entered: after
+                .iter()
+                .copied()
+                .filter(|id| !was.contains(id))
+                .collect(),
+            left: before
+                .iter()
+                .copied()
+                .filter(|id| !is.contains(id))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entered.is_empty() && self.left.is_empty()
+    }
+}
 
 pub struct EcanConfig {
+    /// Share of a link's STI it passes to its members, at full conductance
     pub spread_fraction: f64,
+    /// Share of `spread_fraction` an atom passes up to the links holding it
+    pub incoming_share: f64,
+    /// Conductance of a link with no evidence; a link's truth value raises
+    /// it toward 1 in proportion to strength × confidence
+    pub tv_floor: f64,
+    /// Per-type factor on a link's conductance; types not listed conduct
+    /// fully
+    pub diffusion: HashMap<AtomType, f64>,
+    /// Out of the focus of a cycle, an atom keeps this share of its STI ...
     pub decay_factor: f64,
+    /// Wage paid to an activated atom, before its type's weight
     pub initial_sti: f64,
+    /// ... less this flat rent; both go back to the bank
     pub rent: f64,
+    /// Which atoms are in the attentional focus
+    pub focus: FocusBoundary,
+    /// LTI earned each time an atom enters the focus
+    pub lti_gain: f64,
+    /// LTI paid back each cycle by atoms outside the focus
+    pub lti_rent: f64,
+    /// Forget once the space holds more atoms than this (None = never)
+    pub max_atoms: Option<usize>,
+    /// Only atoms below both limits may be forgotten
+    pub forget_sti: f64,
+    pub forget_lti: f64,
+    /// How far one cycle moves a Hebbian link's strength toward 1 (both
+    /// members in focus) or 0 (only one)
+    pub hebbian_rate: f64,
+    /// Share of a node's STI that flows along its Hebbian links, weighted
+    /// by their strength × confidence
+    pub hebbian_spread: f64,
+    /// Only the top nodes of the focus are paired
+    pub hebbian_max_focus: usize,
+    /// At most this many Hebbian links are created per cycle
+    pub hebbian_max_new: usize,
+    /// A Hebbian link whose strength decays below this is removed
+    pub hebbian_forget: f64,
+}
+
+impl EcanConfig {
+    /// How readily importance crosses `link`, in 0..=1: its type's
+    /// diffusion coefficient times its truth-value weight
+    pub fn conductance(&self, link: &Atom) -> f64 {
+        let evidence = link.tv.strength * link.tv.confidence;
+        let tv_weight = self.tv_floor + (1.0 - self.tv_floor) * evidence;
+        self.type_diffusion(link.atom_type) * tv_weight.clamp(0.0, 1.0)
+    }
+
+    fn type_diffusion(&self, atom_type: AtomType) -> f64 {
+        self.diffusion
+            .get(&atom_type)
+            .copied()
+            .unwrap_or(1.0)
+            .clamp(0.0, 1.0)
+    }
 }
 
 impl Default for EcanConfig {
     fn default() -> Self {
         Self {
             spread_fraction: 0.3,
+            incoming_share: 0.3,
+            tv_floor: 0.1,
+            // A negation shouldn't prime what it negates as much as a fact
+            diffusion: HashMap::from([(AtomType::NotLink, 0.2)]),
             decay_factor: 0.7,
             initial_sti: 40.0,
             rent: 0.5,
+            focus: FocusBoundary::Absolute(10.0),
+            lti_gain: 1.0,
+            lti_rent: 0.01,
+            max_atoms: None,
+            forget_sti: 1.0,
+            forget_lti: 2.0,
+            hebbian_rate: 0.2,
+            hebbian_spread: 0.2,
+            hebbian_max_focus: 8,
+            hebbian_max_new: 8,
+            hebbian_forget: 0.1,
         }
     }
 }
 
+/// An atom removed by `forget`
+#[derive(Debug, Clone)]
+pub struct Forgotten {
+    pub id: AtomId,
+    /// `format_atom` of the atom before it went
+    pub desc: String,
+    /// The atom as Atomese, so it can be archived and re-imported
+    pub atomese: String,
+}
+
 #[derive(Debug)]
 pub struct StiChange {
     pub id: AtomId,
@@ -39,7 +220,30 @@ impl StiChange {
     }
 }
 
-/// Spread attention from activated atoms through the graph
+/// Share of `initial_sti` an activated atom of this type is paid
+fn wage_weight(atom_type: AtomType) -> f64 {
+    match atom_type {
+        AtomType::ListLink => 1.0,
+        AtomType::EvaluationLink
+        | AtomType::InheritanceLink
+        | AtomType::SimilarityLink
+        | AtomType::MemberLink
+        | AtomType::ImplicationLink
+        | AtomType::ContextLink => 0.85,
+        AtomType::AndLink | AtomType::OrLink | AtomType::NotLink => 0.7,
+        AtomType::HebbianLink | AtomType::AsymmetricHebbianLink => 0.3,
+        AtomType::ConceptNode => 0.95,
+        AtomType::PredicateNode | AtomType::SchemaNode => 0.5,
+        AtomType::NumberNode => 0.4,
+        AtomType::VariableNode => 0.2,
+    }
+}
+
+/// One ECAN cycle over a conserved economy: activated atoms are paid wages
+/// from the attention bank, importance spreads by transfer between atoms,
+/// and every other atom pays rent back. STI is never created or destroyed,
+/// so the bank's balance plus all atoms' STI stays at `STI_FUNDS`. Nodes
+/// that end the cycle in the focus together strengthen their Hebbian links.
 pub fn spread_attention(
     space: &mut AtomSpace,
     activated: &[AtomId],
@@ -47,6 +251,7 @@ pub fn spread_attention(
 ) -> Vec<StiChange> {
     let all_ids = space.all_ids();
     let activated_set: HashSet<AtomId> = activated.iter().copied().collect();
+    let focus_before: HashSet<AtomId> = config.focus.ids(space).into_iter().collect();
 
     // Snapshot current STI values
     let old_sti: HashMap<AtomId, f64> = all_ids
@@ -54,69 +259,120 @@ pub fn spread_attention(
         .filter_map(|&id| space.get(id).map(|a| (id, a.av.sti)))
         .collect();
 
-    // Phase 1: Boost activated atoms
-    for &id in activated {
-        if let Some(atom) = space.get_mut(id) {
-            let boost = match atom.atom_type {
-                crate::atom::AtomType::ListLink => config.initial_sti,
-                crate::atom::AtomType::EvaluationLink | crate::atom::AtomType::InheritanceLink => {
-                    config.initial_sti * 0.85
-                }
-                crate::atom::AtomType::ConceptNode => config.initial_sti * 0.95,
-                crate::atom::AtomType::PredicateNode => config.initial_sti * 0.5,
-            };
-            atom.av.sti += boost;
-        }
+    // Phase 1: Wages for activated atoms, scaled down if the bank is short
+    let wages: Vec<(AtomId, f64)> = activated_set
+        .iter()
+        .filter_map(|&id| {
+            space
+                .get(id)
+                .map(|a| (id, config.initial_sti * wage_weight(a.atom_type)))
+        })
+        .collect();
+    let payroll: f64 = wages.iter().map(|&(_, w)| w).sum();
+    let scale = if payroll > 0.0 {
+        (space.bank().sti / payroll).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    for (id, wage) in wages {
+        let sti = space.get(id).map_or(0.0, |a| a.av.sti);
+        space.set_sti(id, sti + wage * scale);
     }
 
-    // Phase 2: Collect spreading amounts (read-only pass)
-    let mut spreads: Vec<(AtomId, f64)> = Vec::new();
+    // Phase 2: Collect transfers (read-only pass); a source pays what it spreads
+    let mut delta: HashMap<AtomId, f64> = HashMap::new();
     for &id in &all_ids {
-        let (sti, outgoing, is_link) = match space.get(id) {
-            Some(a) => (a.av.sti, a.outgoing.clone(), a.atom_type.is_link()),
-            None => continue,
+        let Some(atom) = space.get(id) else {
+            continue;
         };
+        let sti = atom.av.sti;
         if sti < 1.0 {
             continue;
         }
 
-        // Links spread STI to their outgoing atoms
-        if is_link && !outgoing.is_empty() {
-            let amount = sti * config.spread_fraction / outgoing.len() as f64;
-            for &tgt in &outgoing {
-                spreads.push((tgt, amount));
+        // Links spread STI to their outgoing atoms, as far as they conduct
+        let targets: Vec<AtomId> = atom
+            .outgoing
+            .iter()
+            .copied()
+            .filter(|&t| space.get(t).is_some())
+            .collect();
+        if atom.atom_type.is_link() && !targets.is_empty() {
+            let total = sti * config.spread_fraction * config.conductance(atom);
+            let amount = total / targets.len() as f64;
+            for &tgt in &targets {
+                *delta.entry(tgt).or_default() += amount;
+            }
+            *delta.entry(id).or_default() -= total;
+        }
+
+        // Nodes spread to their Hebbian associates, directly
+        let associates = hebbian_associates(space, config, id);
+        let weight: f64 = associates.iter().map(|&(_, w)| w).sum();
+        if weight > 0.0 {
+            let total = sti * config.hebbian_spread * weight.min(1.0);
+            for &(other, w) in &associates {
+                *delta.entry(other).or_default() += total * w / weight;
             }
+            *delta.entry(id).or_default() -= total;
         }
 
-        // All atoms spread weakly to incoming links
-        let incoming = space.get_incoming(id);
+        // All atoms spread weakly to incoming structural links; each link's
+        // share is scaled by its conductance and the rest stays put
+        let incoming: Vec<(AtomId, f64)> = space
+            .incoming_links(id)
+            .iter()
+            .filter_map(|&l| space.get(l))
+            .filter(|l| !l.atom_type.is_hebbian())
+            .map(|l| (l.id, config.conductance(l)))
+            .collect();
         if !incoming.is_empty() {
-            let amount = sti * config.spread_fraction * 0.3 / incoming.len() as f64;
-            for &link_id in &incoming {
-                spreads.push((link_id, amount));
+            let share =
+                sti * config.spread_fraction * config.incoming_share / incoming.len() as f64;
+            for &(link_id, conductance) in &incoming {
+                let amount = share * conductance;
+                *delta.entry(link_id).or_default() += amount;
+                *delta.entry(id).or_default() -= amount;
             }
         }
     }
-
-    // Apply spreads
-    for (id, amount) in spreads {
-        if let Some(atom) = space.get_mut(id) {
-            atom.av.sti += amount;
+    for (id, amount) in delta {
+        if let Some(sti) = space.get(id).map(|a| a.av.sti) {
+            space.set_sti(id, sti + amount);
         }
     }
 
-    // Phase 3: Decay non-activated atoms
+    // Phase 3: Rent from non-activated atoms, back into the bank
     for &id in &all_ids {
         if activated_set.contains(&id) {
             continue;
         }
-        if let Some(atom) = space.get_mut(id) {
-            if atom.av.sti > 0.0 {
-                atom.av.sti = (atom.av.sti * config.decay_factor - config.rent).max(0.0);
+        if let Some(sti) = space.get(id).map(|a| a.av.sti) {
+            if sti > 0.0 {
+                space.set_sti(id, (sti * config.decay_factor - config.rent).max(0.0));
             }
         }
     }
 
+    // Phase 4: Atoms entering the focus earn LTI from the bank; the rest pay
+    // LTI rent
+    let focus_after = config.focus.ids(space);
+    let in_focus: HashSet<AtomId> = focus_after.iter().copied().collect();
+    for &id in &all_ids {
+        let Some(av) = space.get(id).map(|a| a.av) else {
+            continue;
+        };
+        if in_focus.contains(&id) && !focus_before.contains(&id) {
+            let gain = config.lti_gain.min(space.bank().lti.max(0.0));
+            space.set_lti(id, av.lti + gain);
+        } else if !in_focus.contains(&id) && av.lti > 0.0 {
+            space.set_lti(id, (av.lti - config.lti_rent).max(0.0));
+        }
+    }
+
+    // Phase 5: Learn from who shares the focus this cycle
+    update_hebbian(space, config, &focus_after, &focus_before, &activated_set);
+
     // Generate change records
     let mut changes = Vec::new();
     for &id in &all_ids {
@@ -147,6 +403,178 @@ pub fn spread_attention(
     changes
 }
 
+/// Nodes `id` passes STI to along Hebbian links, with the link's
+/// strength × confidence times its type's diffusion coefficient; an
+/// AsymmetricHebbianLink only runs forward
+fn hebbian_associates(space: &AtomSpace, config: &EcanConfig, id: AtomId) -> Vec<(AtomId, f64)> {
+    space
+        .incoming_links(id)
+        .iter()
+        .filter_map(|&l| space.get(l))
+        .filter(|l| match l.atom_type {
+            AtomType::HebbianLink => true,
+            AtomType::AsymmetricHebbianLink => l.outgoing[0] == id,
+            _ => false,
+        })
+        .filter_map(|l| {
+            let other = *l.outgoing.iter().find(|&&o| o != id)?;
+            let weight = l.tv.strength * l.tv.confidence * config.type_diffusion(l.atom_type);
+            Some((other, weight))
+        })
+        .filter(|&(_, w)| w > 0.0)
+        .collect()
+}
+
+/// Hebbian learning over the nodes in `focus`. Only nodes the input
+/// `activated` count as sharing it: a node that sits in focus on importance
+/// spread to it, Hebbian spread included, has not co-occurred with anything.
+/// Every pair of such nodes gets a HebbianLink, created on first
+/// co-occurrence and moved toward strength 1 after; a link with a member in
+/// focus that did not co-occur moves toward 0. A node activated as it enters
+/// the focus while A was already there gets an AsymmetricHebbianLink A→B,
+/// since A led to it; A→B is updated whenever A is in focus, by whether B
+/// co-occurs. Each update adds one observation, so confidence grows with the
+/// number of cycles seen. At most `hebbian_max_new` links are created per
+/// cycle, directed ones first, and links that fall below `hebbian_forget`
+/// are removed.
+fn update_hebbian(
+    space: &mut AtomSpace,
+    config: &EcanConfig,
+    focus: &[AtomId],
+    before: &HashSet<AtomId>,
+    activated: &HashSet<AtomId>,
+) {
+    let mut focus: Vec<AtomId> = focus
+        .iter()
+        .copied()
+        .filter(|&id| space.get(id).is_some_and(|a| a.atom_type.is_node()))
+        .collect();
+    focus.truncate(config.hebbian_max_focus);
+    let in_focus: HashSet<AtomId> = focus.iter().copied().collect();
+    let together: Vec<AtomId> = focus
+        .iter()
+        .copied()
+        .filter(|id| activated.contains(id))
+        .collect();
+    let co_occurs: HashSet<AtomId> = together.iter().copied().collect();
+
+    let observe = |tv: TruthValue, hit: bool| {
+        let target = if hit { 1.0 } else { 0.0 };
+        let strength = tv.strength + config.hebbian_rate * (target - tv.strength);
+        TruthValue::from_count(strength, tv.count() + 1.0)
+    };
+
+    // Existing links touching the focus, each updated once
+    let mut seen = HashSet::new();
+    let mut faded = Vec::new();
+    for &id in &focus {
+        for l in space.get_incoming(id) {
+            if !seen.insert(l) {
+                continue;
+            }
+            let Some(link) = space.get(l) else { continue };
+            let hit = match link.atom_type {
+                AtomType::HebbianLink => link.outgoing.iter().all(|o| co_occurs.contains(o)),
+                AtomType::AsymmetricHebbianLink if in_focus.contains(&link.outgoing[0]) => {
+                    co_occurs.contains(&link.outgoing[1])
+                }
+                _ => continue,
+            };
+            let tv = observe(link.tv, hit);
+            space.set_tv(l, tv);
+            if tv.strength < config.hebbian_forget {
+                faded.push(l);
+            }
+        }
+    }
+    for l in faded {
+        let _ = space.remove_atom(l, RemovalPolicy::Refuse);
+    }
+
+    // First co-occurrences: who was there when each newcomer arrived, then
+    // every pair, hottest first
+    let stayed = focus.iter().filter(|id| before.contains(id));
+    let entered: Vec<AtomId> = together
+        .iter()
+        .copied()
+        .filter(|id| !before.contains(id))
+        .collect();
+    let directed = stayed.flat_map(|&a| {
+        entered
+            .iter()
+            .map(move |&b| (AtomType::AsymmetricHebbianLink, a, b))
+    });
+    let pairs = together.iter().enumerate().flat_map(|(i, &a)| {
+        together[i + 1..]
+            .iter()
+            .map(move |&b| (AtomType::HebbianLink, a, b))
+    });
+    let mut created = 0;
+    for (link_type, a, b) in directed.chain(pairs) {
+        if created == config.hebbian_max_new {
+            break;
+        }
+        if space.find_link(link_type, &[a, b]).is_none() {
+            space.add_link(link_type, vec![a, b], TruthValue::from_count(1.0, 1.0));
+            created += 1;
+        }
+    }
+}
+
+/// Forgetting agent: once the space holds more than `config.max_atoms`,
+/// remove the atoms with the lowest LTI (then STI) until it fits again.
+/// Atoms at or above `forget_sti` or `forget_lti`, atoms in `protected`,
+/// and atoms something still links to are kept, so the space can stay
+/// over its limit. Links go before the atoms they reference.
+pub fn forget(
+    space: &mut AtomSpace,
+    config: &EcanConfig,
+    protected: &HashSet<AtomId>,
+) -> Vec<Forgotten> {
+    let Some(max) = config.max_atoms else {
+        return Vec::new();
+    };
+    if space.size() <= max {
+        return Vec::new();
+    }
+
+    let mut candidates: Vec<(AtomId, f64, f64)> = space
+        .all_ids()
+        .into_iter()
+        .filter(|id| !protected.contains(id))
+        .filter_map(|id| space.get(id).map(|a| (id, a.av.lti, a.av.sti)))
+        .filter(|&(_, lti, sti)| lti < config.forget_lti && sti < config.forget_sti)
+        .collect();
+    candidates.sort_by(|a, b| {
+        (a.1, a.2)
+            .partial_cmp(&(b.1, b.2))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+
+    // Removing a link can free its members, so sweep until nothing changes
+    let mut forgotten = Vec::new();
+    let mut progress = true;
+    while progress && space.size() > max {
+        progress = false;
+        for &(id, _, _) in &candidates {
+            if space.size() <= max {
+                break;
+            }
+            if space.get(id).is_none() || !space.get_incoming(id).is_empty() {
+                continue;
+            }
+            let desc = space.format_atom(id);
+            let atomese = atomese::to_atomese(space, id).unwrap_or_default();
+            if space.remove_atom(id, RemovalPolicy::Refuse).is_ok() {
+                forgotten.push(Forgotten { id, desc, atomese });
+                progress = true;
+            }
+        }
+    }
+    forgotten
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,4 +668,319 @@ mod tests {
             pn_sti
         );
     }
+
+    #[test]
+    fn lti_accrues_on_each_entry_into_focus() {
+        let mut space = AtomSpace::new();
+        let (id, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let config = EcanConfig::default();
+        spread_attention(&mut space, &[id], &config);
+        assert_eq!(space.get(id).unwrap().av.lti, 1.0);
+        // Staying in focus earns nothing more
+        spread_attention(&mut space, &[id], &config);
+        assert_eq!(space.get(id).unwrap().av.lti, 1.0);
+        while config.focus.ids(&space).contains(&id) {
+            spread_attention(&mut space, &[], &config);
+        }
+        // Out of focus for one cycle: one LTI rent
+        spread_attention(&mut space, &[id], &config);
+        let lti = space.get(id).unwrap().av.lti;
+        assert!((lti - (2.0 - config.lti_rent)).abs() < 1e-9, "lti {}", lti);
+    }
+
+    fn total_sti(space: &AtomSpace) -> f64 {
+        space.bank().sti
+            + space
+                .all_atoms_sorted()
+                .iter()
+                .map(|a| a.av.sti)
+                .sum::<f64>()
+    }
+
+    #[test]
+    fn funds_are_conserved() {
+        use crate::atomspace::{RemovalPolicy, LTI_FUNDS, STI_FUNDS};
+        let mut space = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut space);
+        let cat = space.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let mammal = space.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let link = space
+            .find_link(AtomType::InheritanceLink, &[cat, mammal])
+            .unwrap();
+        let config = EcanConfig::default();
+        for round in 0..10 {
+            let activated = if round % 3 == 0 {
+                vec![cat, link]
+            } else {
+                vec![mammal]
+            };
+            spread_attention(&mut space, &activated, &config);
+            assert!((total_sti(&space) - STI_FUNDS).abs() < 1e-6);
+        }
+        assert!(space.bank().sti < STI_FUNDS, "wages were paid");
+        let lti: f64 = space.all_atoms_sorted().iter().map(|a| a.av.lti).sum();
+        assert!(lti > 0.0 && (space.bank().lti + lti - LTI_FUNDS).abs() < 1e-6);
+
+        // Removing an atom refunds what it held
+        space.remove_atom(cat, RemovalPolicy::Cascade).unwrap();
+        assert!((total_sti(&space) - STI_FUNDS).abs() < 1e-6);
+    }
+
+    #[test]
+    fn wages_never_overdraw_the_bank() {
+        let mut space = AtomSpace::new();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (b, _) = space.add_node(AtomType::ConceptNode, "dog", tv(0.9, 0.8));
+        let bank = space.bank();
+        space.set_bank(crate::atomspace::AttentionBank { sti: 19.0, ..bank });
+        spread_attention(&mut space, &[a, b], &EcanConfig::default());
+        // Each wants 38; the 19 left is split between them
+        assert!(space.bank().sti.abs() < 1e-9);
+        assert!((space.get(a).unwrap().av.sti - 9.5).abs() < 1e-9);
+        assert!((space.get(b).unwrap().av.sti - 9.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn forgetting_removes_unimportant_unprotected_atoms() {
+        let mut space = AtomSpace::new();
+        let (keep, _) = space.add_node(AtomType::ConceptNode, "thing", tv(0.9, 0.8));
+        let (a, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (b, _) = space.add_node(AtomType::ConceptNode, "mammal", tv(0.9, 0.8));
+        let (lid, _) = space.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.95, 0.9));
+        let (hot, _) = space.add_node(AtomType::ConceptNode, "dog", tv(0.9, 0.8));
+        space.set_sti(hot, 20.0);
+        space.set_lti(b, 5.0);
+
+        let mut config = EcanConfig::default();
+        assert!(forget(&mut space, &config, &HashSet::new()).is_empty());
+        config.max_atoms = Some(3);
+        let gone = forget(&mut space, &config, &HashSet::from([keep]));
+        let ids: Vec<_> = gone.iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![lid, a], "the link goes before its member");
+        assert!(gone[0].atomese.starts_with("(InheritanceLink"));
+        assert_eq!(space.size(), 3);
+        assert!(space.get(keep).is_some() && space.get(b).is_some());
+
+        // Nothing left that may go: the space stays over its limit
+        config.max_atoms = Some(1);
+        assert!(forget(&mut space, &config, &HashSet::from([keep])).is_empty());
+    }
+
+    #[test]
+    fn hebbian_links_learned_from_shared_focus() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (fish, _) = space.add_node(AtomType::ConceptNode, "fish", tv(0.9, 0.8));
+        let (dog, _) = space.add_node(AtomType::ConceptNode, "dog", tv(0.9, 0.8));
+        let config = EcanConfig::default();
+        spread_attention(&mut space, &[cat, fish], &config);
+        let link = space
+            .find_link(AtomType::HebbianLink, &[fish, cat])
+            .expect("cat and fish shared the focus");
+        assert!(space
+            .find_link(AtomType::HebbianLink, &[cat, dog])
+            .is_none());
+        let first = space.get(link).unwrap().tv;
+        spread_attention(&mut space, &[cat, fish], &config);
+        let second = space.get(link).unwrap().tv;
+        assert!(second.confidence > first.confidence);
+
+        // cat alone in focus: the association weakens, and fish gets a share
+        // of cat's importance along it
+        while config.focus.ids(&space).contains(&fish) {
+            spread_attention(&mut space, &[], &config);
+        }
+        let before = space.get(fish).unwrap().av.sti;
+        spread_attention(&mut space, &[cat], &config);
+        assert!(space.get(link).unwrap().tv.strength < second.strength);
+        // Without the link fish would only have paid rent
+        let after = space.get(fish).unwrap().av.sti;
+        assert!(after > before * config.decay_factor - config.rent);
+    }
+
+    #[test]
+    fn newcomers_learn_directed_links_from_the_focus() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (fish, _) = space.add_node(AtomType::ConceptNode, "fish", tv(0.9, 0.8));
+        let config = EcanConfig::default();
+        spread_attention(&mut space, &[cat], &config);
+        spread_attention(&mut space, &[cat, fish], &config);
+        // cat was already attended when fish arrived
+        assert!(space
+            .find_link(AtomType::AsymmetricHebbianLink, &[cat, fish])
+            .is_some());
+        assert!(space
+            .find_link(AtomType::AsymmetricHebbianLink, &[fish, cat])
+            .is_none());
+        assert!(space
+            .find_link(AtomType::HebbianLink, &[cat, fish])
+            .is_some());
+    }
+
+    #[test]
+    fn hebbian_growth_is_capped_and_faded_links_removed() {
+        let mut space = AtomSpace::new();
+        let ids: Vec<AtomId> = (0..6)
+            .map(|i| {
+                let name = format!("n{}", i);
+                space.add_node(AtomType::ConceptNode, &name, tv(0.9, 0.8)).0
+            })
+            .collect();
+        let config = EcanConfig {
+            hebbian_max_new: 4,
+            ..EcanConfig::default()
+        };
+        spread_attention(&mut space, &ids, &config);
+        assert_eq!(space.get_by_type(AtomType::HebbianLink).len(), 4, "not 15");
+
+        // A weak association that misses again falls below the threshold
+        let (weak, _) = space.add_link(
+            AtomType::AsymmetricHebbianLink,
+            vec![ids[0], ids[5]],
+            tv(0.11, 0.5),
+        );
+        space.set_sti(ids[5], 0.0);
+        let config = EcanConfig {
+            hebbian_max_new: 0,
+            ..config
+        };
+        spread_attention(&mut space, &ids[..1], &config);
+        assert!(space.get(weak).is_none());
+    }
+
+    #[test]
+    fn asymmetric_hebbian_links_spread_forward_only() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (fish, _) = space.add_node(AtomType::ConceptNode, "fish", tv(0.9, 0.8));
+        let (link, _) = space.add_link(
+            AtomType::AsymmetricHebbianLink,
+            vec![cat, fish],
+            tv(0.9, 0.9),
+        );
+        let config = EcanConfig {
+            hebbian_max_focus: 0,
+            ..EcanConfig::default()
+        };
+        spread_attention(&mut space, &[fish], &config);
+        assert_eq!(
+            space.get(cat).unwrap().av.sti,
+            0.0,
+            "fish does not lead to cat"
+        );
+        space.set_sti(fish, 0.0);
+        spread_attention(&mut space, &[cat], &config);
+        assert!(space.get(fish).unwrap().av.sti > 0.0);
+        assert_eq!(
+            space.get(link).unwrap().av.sti,
+            0.0,
+            "learned links hold no STI"
+        );
+    }
+
+    #[test]
+    fn focus_boundaries() {
+        let mut space = AtomSpace::new();
+        let ids: Vec<AtomId> = (1..=10)
+            .map(|i| {
+                let name = format!("n{}", i);
+                let (id, _) = space.add_node(AtomType::ConceptNode, &name, tv(0.9, 0.8));
+                space.set_sti(id, i as f64 * 2.0);
+                id
+            })
+            .collect();
+        space.add_node(AtomType::ConceptNode, "cold", tv(0.9, 0.8));
+        let top = |n: usize| ids.iter().rev().take(n).copied().collect::<Vec<_>>();
+
+        assert_eq!(FocusBoundary::Absolute(15.0).ids(&space), top(3));
+        assert_eq!(FocusBoundary::TopK(4).ids(&space), top(4));
+        // Percentiles rank only atoms holding STI, not the cold one
+        assert_eq!(FocusBoundary::Percentile(80.0).ids(&space), top(2));
+        assert_eq!(FocusBoundary::Percentile(0.0).ids(&space), top(10));
+        assert!(FocusBoundary::Percentile(100.0).ids(&space).is_empty());
+
+        assert_eq!(
+            FocusBoundary::from_name("12.5"),
+            Some(FocusBoundary::Absolute(12.5))
+        );
+        assert_eq!(
+            FocusBoundary::from_name("top:5"),
+            Some(FocusBoundary::TopK(5))
+        );
+        assert_eq!(
+            FocusBoundary::from_name("p90"),
+            Some(FocusBoundary::Percentile(90.0))
+        );
+        assert_eq!(FocusBoundary::from_name("p120"), None);
+        assert_eq!(FocusBoundary::from_name("hot"), None);
+    }
+
+    #[test]
+    fn focus_shift_reports_entries_and_exits() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (dog, _) = space.add_node(AtomType::ConceptNode, "dog", tv(0.9, 0.8));
+        let config = EcanConfig {
+            focus: FocusBoundary::TopK(1),
+            ..EcanConfig::default()
+        };
+        spread_attention(&mut space, &[cat], &config);
+        let before = config.focus.ids(&space);
+        assert_eq!(before, vec![cat]);
+        spread_attention(&mut space, &[dog], &config);
+        let shift = FocusShift::between(&before, &config.focus.ids(&space));
+        assert_eq!(shift.entered, vec![dog]);
+        assert_eq!(shift.left, vec![cat]);
+        // Entering a top-1 focus earns LTI just as crossing a threshold does
+        assert_eq!(space.get(dog).unwrap().av.lti, config.lti_gain);
+        assert!(FocusShift::between(&[dog], &[dog]).is_empty());
+    }
+
+    #[test]
+    fn confident_links_conduct_more_importance() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (mammal, _) = space.add_node(AtomType::ConceptNode, "mammal", tv(0.9, 0.8));
+        let (rock, _) = space.add_node(AtomType::ConceptNode, "rock", tv(0.9, 0.8));
+        let (mineral, _) = space.add_node(AtomType::ConceptNode, "mineral", tv(0.9, 0.8));
+        let (sure, _) = space.add_link(AtomType::InheritanceLink, vec![cat, mammal], tv(0.95, 0.9));
+        let (guess, _) =
+            space.add_link(AtomType::InheritanceLink, vec![rock, mineral], tv(0.6, 0.1));
+        let (list, _) = space.add_link(AtomType::ListLink, vec![rock, cat], tv(0.0, 0.0));
+        let config = EcanConfig::default();
+        assert!(config.conductance(space.get(sure).unwrap()) > 0.8);
+        assert!((config.conductance(space.get(list).unwrap()) - config.tv_floor).abs() < 1e-9);
+
+        spread_attention(&mut space, &[sure, guess], &config);
+        let mammal_sti = space.get(mammal).unwrap().av.sti;
+        let mineral_sti = space.get(mineral).unwrap().av.sti;
+        assert!(
+            mammal_sti > 2.0 * mineral_sti,
+            "confident link gave {}, weak one {}",
+            mammal_sti,
+            mineral_sti
+        );
+        // The weak link kept what it didn't pass on
+        assert!(space.get(guess).unwrap().av.sti > space.get(sure).unwrap().av.sti);
+    }
+
+    #[test]
+    fn diffusion_coefficients_per_link_type() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", tv(0.9, 0.8));
+        let (mammal, _) = space.add_node(AtomType::ConceptNode, "mammal", tv(0.9, 0.8));
+        let (link, _) = space.add_link(AtomType::InheritanceLink, vec![cat, mammal], tv(0.95, 0.9));
+        let (not, _) = space.add_link(AtomType::NotLink, vec![link], tv(0.9, 0.9));
+        let mut config = EcanConfig::default();
+        assert!(config.conductance(space.get(not).unwrap()) < 0.2 + 1e-9);
+
+        config.diffusion.insert(AtomType::InheritanceLink, 0.0);
+        spread_attention(&mut space, &[link, cat], &config);
+        assert_eq!(space.get(mammal).unwrap().av.sti, 0.0, "no diffusion down");
+        assert!(space.get(link).unwrap().av.sti > 0.0, "the link was paid");
+        // cat passes nothing up the closed link either
+        let cat_only = config.initial_sti * wage_weight(AtomType::ConceptNode);
+        assert!((space.get(cat).unwrap().av.sti - cat_only).abs() < 1e-9);
+    }
 }
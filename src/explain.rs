@@ -0,0 +1,224 @@
+//! Explain — proof trees for derived atoms
+//! Follows stored provenance from a conclusion back to asserted facts.
+
+use serde::Serialize;
+
+use crate::atom::*;
+use crate::atomspace::AtomSpace;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofNode {
+    pub atom: AtomId,
+    pub text: String,
+    /// Current truth value; None if the atom has since been removed
+    pub tv: Option<TruthValue>,
+    /// Rule that derived the atom; None for asserted facts
+    pub rule: Option<String>,
+    pub premises: Vec<ProofNode>,
+}
+
+impl ProofNode {
+    pub fn is_asserted(&self) -> bool {
+        self.rule.is_none()
+    }
+
+    /// Number of derivation steps in the tree
+    pub fn steps(&self) -> usize {
+        self.premises.iter().map(ProofNode::steps).sum::<usize>() + usize::from(!self.is_asserted())
+    }
+}
+
+/// Resolve a user-facing atom reference: `cat->mammal` / `cat→mammal` for an
+/// InheritanceLink, otherwise a node name
+pub fn resolve(space: &AtomSpace, spec: &str) -> Option<AtomId> {
+    let spec = spec.trim();
+    if let Some((src, tgt)) = spec
+        .split_once("->")
+        .or_else(|| spec.split_once('\u{2192}'))
+    {
+        let src = space.find_node(AtomType::ConceptNode, src.trim())?;
+        let tgt = space.find_node(AtomType::ConceptNode, tgt.trim())?;
+        return space.find_link(AtomType::InheritanceLink, &[src, tgt]);
+    }
+    space.find_named(spec)
+}
+
+/// Proof tree for `id`, or None if the atom does not exist
+pub fn explain(space: &AtomSpace, id: AtomId) -> Option<ProofNode> {
+    space.get(id)?;
+    Some(build(space, id, &mut Vec::new()))
+}
+
+fn build(space: &AtomSpace, id: AtomId, path: &mut Vec<AtomId>) -> ProofNode {
+    let Some(atom) = space.get(id) else {
+        return ProofNode {
+            atom: id,
+            text: format!("atom {:04x} (removed)", id),
+            tv: None,
+            rule: None,
+            premises: Vec::new(),
+        };
+    };
+    let mut node = ProofNode {
+        atom: id,
+        text: space.format_atom(id),
+        tv: Some(atom.tv),
+        rule: None,
+        premises: Vec::new(),
+    };
+    // Premises always predate their conclusion, but a hand-edited snapshot
+    // could still loop; stop at the first repeat
+    if path.contains(&id) {
+        return node;
+    }
+    if let Some(p) = space.provenance(id) {
+        node.rule = Some(p.rule.clone());
+        path.push(id);
+        node.premises = p
+            .premises
+            .iter()
+            .map(|&pid| build(space, pid, path))
+            .collect();
+        path.pop();
+    }
+    node
+}
+
+/// Render a proof tree as indented lines, conclusion first
+pub fn render(root: &ProofNode) -> Vec<String> {
+    render_tree(
+        root,
+        &|node: &ProofNode| {
+            let tv = node.tv.map(|tv| format!(" ({})", tv)).unwrap_or_default();
+            match &node.rule {
+                Some(rule) => format!("\u{22a2} {}{} \u{2190} {}", node.text, tv, rule),
+                None => format!("\u{25cf} {}{} asserted", node.text, tv),
+            }
+        },
+        &|node| &node.premises,
+    )
+}
+
+/// Draw any tree with box-drawing branches; `label` renders one node and
+/// `children` lists its premises
+pub fn render_tree<T>(
+    root: &T,
+    label: &dyn Fn(&T) -> String,
+    children: &dyn Fn(&T) -> &[T],
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    render_into(root, "", "", label, children, &mut lines);
+    lines
+}
+
+fn render_into<T>(
+    node: &T,
+    lead: &str,
+    indent: &str,
+    label: &dyn Fn(&T) -> String,
+    children: &dyn Fn(&T) -> &[T],
+    lines: &mut Vec<String>,
+) {
+    lines.push(format!("{}{}", lead, label(node)));
+    let kids = children(node);
+    for (i, child) in kids.iter().enumerate() {
+        let (branch, cont) = if i + 1 == kids.len() {
+            ("\u{2514}\u{2500} ", "   ")
+        } else {
+            ("\u{251c}\u{2500} ", "\u{2502}  ")
+        };
+        render_into(
+            child,
+            &format!("{}{}", indent, branch),
+            &format!("{}{}", indent, cont),
+            label,
+            children,
+            lines,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atomspace::RemovalPolicy;
+    use crate::ontology;
+    use crate::pln;
+
+    fn derived_space() -> AtomSpace {
+        let mut s = AtomSpace::new();
+        ontology::load_base_ontology(&mut s);
+        pln::forward_chain(&mut s, 3);
+        s
+    }
+
+    fn leaves(node: &ProofNode, out: &mut Vec<String>) {
+        if node.premises.is_empty() {
+            out.push(node.text.clone());
+        }
+        for p in &node.premises {
+            leaves(p, out);
+        }
+    }
+
+    #[test]
+    fn explains_down_to_asserted_facts() {
+        let s = derived_space();
+        let id = resolve(&s, "cucumber->thing").unwrap();
+        let proof = explain(&s, id).unwrap();
+        assert_eq!(proof.rule.as_deref(), Some("deduction"));
+        let mut facts = Vec::new();
+        leaves(&proof, &mut facts);
+        // Every leaf is an ontology assertion on the cucumber→thing path
+        for link in [
+            "cucumber\u{2192}vegetable",
+            "vegetable\u{2192}plant",
+            "plant\u{2192}living-thing",
+            "living-thing\u{2192}thing",
+        ] {
+            assert!(
+                facts.iter().any(|f| f.contains(link)),
+                "missing {} in {:?}",
+                link,
+                facts
+            );
+        }
+        assert!(proof.steps() >= 3);
+    }
+
+    #[test]
+    fn asserted_atoms_have_no_rule() {
+        let s = derived_space();
+        let id = resolve(&s, "cat\u{2192}mammal").unwrap();
+        let proof = explain(&s, id).unwrap();
+        assert!(proof.is_asserted());
+        assert!(proof.premises.is_empty());
+        assert_eq!(render(&proof).len(), 1);
+    }
+
+    #[test]
+    fn render_draws_tree() {
+        let s = derived_space();
+        let proof = explain(&s, resolve(&s, "cat->animal").unwrap()).unwrap();
+        let lines = render(&proof);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("\u{22a2} InheritanceLink:[cat\u{2192}animal]"));
+        assert!(lines[0].ends_with("\u{2190} deduction"));
+        assert!(lines[1].starts_with("\u{251c}\u{2500} \u{25cf}"));
+        assert!(lines[2].starts_with("\u{2514}\u{2500} \u{25cf}"));
+    }
+
+    #[test]
+    fn removed_premises_are_marked_and_provenance_dropped() {
+        let mut s = derived_space();
+        let derived = resolve(&s, "cat->animal").unwrap();
+        let premise = resolve(&s, "cat->mammal").unwrap();
+        s.remove_atom(premise, RemovalPolicy::Orphan).unwrap();
+        let proof = explain(&s, derived).unwrap();
+        assert!(proof.premises.iter().any(|p| p.tv.is_none()));
+
+        s.remove_atom(derived, RemovalPolicy::Orphan).unwrap();
+        assert!(s.provenance(derived).is_none());
+        assert!(explain(&s, derived).is_none());
+    }
+}
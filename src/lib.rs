@@ -1,8 +1,16 @@
+pub mod answer;
 pub mod atom;
+pub mod atomese;
 pub mod atomspace;
+pub mod backward;
 pub mod cogloop;
 pub mod ecan;
+pub mod explain;
 pub mod ontology;
 pub mod parse;
+pub mod pattern;
+pub mod persist;
 pub mod pln;
+pub mod rules;
 pub mod tikkun;
+pub mod wal;
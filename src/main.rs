@@ -1,20 +1,95 @@
 use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
 
-use coggy::atomspace::AtomSpace;
+use coggy::atom::AtomId;
+use coggy::atomese;
+use coggy::atomspace::{AtomSpace, MergePolicy, RemovalPolicy};
+use coggy::backward::{self, BackwardConfig};
 use coggy::cogloop;
-use coggy::ecan::EcanConfig;
+use coggy::ecan::{EcanConfig, FocusBoundary};
+use coggy::explain;
 use coggy::ontology;
-use coggy::pln;
+use coggy::pattern::{self, Pattern};
+use coggy::persist;
+use coggy::pln::{self, InferenceControl};
+use coggy::rules::{InferenceRule, RuleRegistry};
 use coggy::tikkun;
 use serde_json::json;
 
 fn main() {
-    let json_mode = std::env::args().any(|a| a == "--json");
+    let args: Vec<String> = std::env::args().collect();
+    let json_mode = args.iter().any(|a| a == "--json");
+    // --snapshot <path> wins over COGGY_SNAPSHOT
+    let snapshot_path: Option<PathBuf> = args
+        .iter()
+        .position(|a| a == "--snapshot")
+        .and_then(|i| args.get(i + 1).cloned())
+        .or_else(|| std::env::var("COGGY_SNAPSHOT").ok())
+        .map(PathBuf::from);
 
-    let mut space = AtomSpace::new();
-    let ecan_config = EcanConfig::default();
+    let mut ecan_config = EcanConfig::default();
+    if let Ok(max) = std::env::var("COGGY_MAX_ATOMS") {
+        match max.parse() {
+            Ok(max) => ecan_config.max_atoms = Some(max),
+            Err(_) => {
+                eprintln!("coggy: COGGY_MAX_ATOMS must be a number, got '{}'", max);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Ok(spec) = std::env::var("COGGY_FOCUS") {
+        match FocusBoundary::from_name(&spec) {
+            Some(focus) => ecan_config.focus = focus,
+            None => {
+                eprintln!(
+                    "coggy: COGGY_FOCUS must be an STI, top:<k> or p<percentile>, got '{}'",
+                    spec
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+    let archive_path = std::env::var("COGGY_ARCHIVE").ok().map(PathBuf::from);
+    let mut control = InferenceControl::default();
+    if let Ok(names) = std::env::var("COGGY_RULES") {
+        let names: Vec<&str> = names.split(',').map(str::trim).collect();
+        if let Err(name) = control.rules.enable_only(&names) {
+            eprintln!(
+                "coggy: unknown rule '{}' in COGGY_RULES ({})",
+                name,
+                control.rules.names().join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
 
-    let loaded = ontology::load_base_ontology(&mut space);
+    let (mut space, restored) = match snapshot_path.as_deref().filter(|p| p.exists()) {
+        Some(path) => match persist::load_snapshot(path) {
+            Ok(space) => (space, true),
+            Err(e) => {
+                eprintln!("coggy: cannot load snapshot {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => (AtomSpace::new(), false),
+    };
+    let loaded = if restored {
+        space.size()
+    } else {
+        ontology::load_base_ontology(&mut space)
+    };
+    if let Ok(name) = std::env::var("COGGY_MERGE") {
+        match MergePolicy::from_name(&name) {
+            Some(policy) => space.set_merge_policy(policy),
+            None => {
+                eprintln!(
+                    "coggy: unknown COGGY_MERGE '{}' (revision or keep-max)",
+                    name
+                );
+                std::process::exit(1);
+            }
+        }
+    }
 
     if json_mode {
         println!(
@@ -23,11 +98,20 @@ fn main() {
                 "event": "init",
                 "atoms_loaded": loaded,
                 "total_atoms": space.size(),
+                "restored": restored,
+                "snapshot": snapshot_path.as_ref().map(|p| p.display().to_string()),
             })
         );
     } else {
         println!("\u{25c8} COGGY \u{2014} Cognitive Architecture (Rust)");
-        println!("  {} atoms loaded from base ontology.", loaded);
+        if restored {
+            println!(
+                "  {} atoms restored from snapshot (turn {}).",
+                loaded, space.turn
+            );
+        } else {
+            println!("  {} atoms loaded from base ontology.", loaded);
+        }
         println!("  AtomSpace: {} total atoms", space.size());
         println!();
         println!("Commands:");
@@ -35,8 +119,17 @@ fn main() {
         println!("  :atoms        \u{2014} show all atoms");
         println!("  :focus        \u{2014} show attention focus (top STI)");
         println!("  :types        \u{2014} show atom type counts");
-        println!("  :infer        \u{2014} run PLN forward chain manually");
+        println!(
+            "  :infer [r..]  \u{2014} run PLN forward chain [deduction|induction|abduction|all]"
+        );
+        println!("  :remove <n>   \u{2014} remove an atom [refuse|cascade|orphan]");
         println!("  :tikkun       \u{2014} run self-repair diagnostics");
+        println!("  :save [path]  \u{2014} write an AtomSpace snapshot");
+        println!("  :query <pat>  \u{2014} match an Atomese pattern with $variables");
+        println!("  :prove <goal> \u{2014} backward-chain a link (:prove cat->living-thing)");
+        println!("  :explain <a>  \u{2014} proof tree for an atom (:explain cucumber->thing)");
+        println!("  :import <f>   \u{2014} load Atomese s-expressions");
+        println!("  :export [f]   \u{2014} write the space as Atomese");
         println!("  :help         \u{2014} show this help");
         println!("  :quit         \u{2014} exit");
         println!();
@@ -67,7 +160,7 @@ fn main() {
                     println!(
                         "{}",
                         json!({"event": "help", "commands": [
-                            ":atoms", ":focus", ":types", ":infer", ":tikkun", ":quit"
+                            ":atoms", ":focus", ":types", ":infer", ":remove", ":tikkun", ":query", ":prove", ":explain", ":save", ":import", ":export", ":quit"
                         ]})
                     );
                 } else {
@@ -83,9 +176,9 @@ fn main() {
             }
             ":focus" | ":f" => {
                 if json_mode {
-                    print_focus_json(&space);
+                    print_focus_json(&space, &ecan_config.focus);
                 } else {
-                    print_focus(&space);
+                    print_focus(&space, &ecan_config.focus);
                 }
             }
             ":types" | ":t" => {
@@ -95,11 +188,13 @@ fn main() {
                     print_types(&space);
                 }
             }
-            ":infer" | ":i" => {
-                if json_mode {
-                    run_infer_json(&mut space);
-                } else {
-                    run_infer(&mut space);
+            cmd if cmd == ":infer" || cmd == ":i" || cmd.starts_with(":infer ") => {
+                let args: Vec<&str> = cmd.split_whitespace().skip(1).collect();
+                match parse_rules(&control.rules, &args) {
+                    Ok(rules) if json_mode => run_infer_json(&mut space, &rules),
+                    Ok(rules) => run_infer(&mut space, &rules),
+                    Err(e) if json_mode => println!("{}", json!({"event": "infer", "error": e})),
+                    Err(e) => println!("\u{26a0} {}", e),
                 }
             }
             ":tikkun" | ":tk" => {
@@ -109,10 +204,50 @@ fn main() {
                     run_tikkun(&space);
                 }
             }
+            cmd if cmd.starts_with(":remove ") || cmd.starts_with(":rm ") => {
+                let args: Vec<&str> = cmd.split_whitespace().skip(1).collect();
+                if json_mode {
+                    run_remove_json(&mut space, &args);
+                } else {
+                    run_remove(&mut space, &args);
+                }
+            }
+            cmd if cmd == ":save" || cmd.starts_with(":save ") => {
+                let path = cmd
+                    .split_whitespace()
+                    .nth(1)
+                    .map(PathBuf::from)
+                    .or_else(|| snapshot_path.clone());
+                save(&space, path.as_deref(), json_mode);
+            }
+            cmd if cmd.starts_with(":query ") || cmd.starts_with(":q ") => {
+                let text = cmd.split_once(' ').map(|(_, t)| t).unwrap_or("");
+                run_query(&space, text, json_mode);
+            }
+            cmd if cmd.starts_with(":prove ") => {
+                run_prove(&space, &cmd[":prove ".len()..], &control.rules, json_mode);
+            }
+            cmd if cmd.starts_with(":explain ") || cmd.starts_with(":why ") => {
+                let spec = cmd.split_once(' ').map(|(_, t)| t).unwrap_or("");
+                run_explain(&space, spec, json_mode);
+            }
+            cmd if cmd.starts_with(":import ") => {
+                let path = cmd[":import ".len()..].trim();
+                run_import(&mut space, Path::new(path), json_mode);
+            }
+            cmd if cmd == ":export" || cmd.starts_with(":export ") => {
+                let path = cmd.split_whitespace().nth(1).map(Path::new);
+                run_export(&space, path, json_mode);
+            }
             input => {
-                let result = cogloop::run(&mut space, input, &ecan_config);
+                let result = cogloop::run_with(&mut space, input, &ecan_config, &control);
+                if let Some(path) = archive_path.as_deref() {
+                    if let Err(e) = persist::append_archive(path, &result.forgotten) {
+                        eprintln!("coggy: cannot archive to {}: {}", path.display(), e);
+                    }
+                }
                 if json_mode {
-                    print_trace_json(&result, &space);
+                    print_trace_json(&result, &space, &ecan_config.focus);
                 } else {
                     print_trace(&result);
                 }
@@ -120,6 +255,10 @@ fn main() {
         }
     }
 
+    if let Some(path) = snapshot_path.as_deref() {
+        save(&space, Some(path), json_mode);
+    }
+
     if json_mode {
         println!(
             "{}",
@@ -130,6 +269,175 @@ fn main() {
     }
 }
 
+fn save(space: &AtomSpace, path: Option<&Path>, json_mode: bool) {
+    let Some(path) = path else {
+        if json_mode {
+            println!("{}", json!({"event": "save", "error": "no snapshot path"}));
+        } else {
+            println!("No snapshot path: use :save <path> or start with --snapshot <path>");
+        }
+        return;
+    };
+    let result = persist::save_snapshot(space, path);
+    if json_mode {
+        match result {
+            Ok(()) => println!(
+                "{}",
+                json!({"event": "save", "path": path.display().to_string(), "total_atoms": space.size()})
+            ),
+            Err(e) => println!("{}", json!({"event": "save", "error": e.to_string()})),
+        }
+    } else {
+        match result {
+            Ok(()) => println!(
+                "\u{2913} Saved {} atoms to {}",
+                space.size(),
+                path.display()
+            ),
+            Err(e) => println!("\u{2717} Snapshot failed: {}", e),
+        }
+    }
+}
+
+fn run_prove(space: &AtomSpace, spec: &str, rules: &RuleRegistry, json_mode: bool) {
+    let Some((source, target)) = backward::parse_goal(space, spec) else {
+        let e = format!("cannot resolve goal '{}' (use src->tgt)", spec);
+        if json_mode {
+            println!("{}", json!({"event": "prove", "error": e}));
+        } else {
+            println!("\u{2717} {}", e);
+        }
+        return;
+    };
+    let config = BackwardConfig::for_rules(rules);
+    let proof = backward::prove(space, source, target, &config);
+    if json_mode {
+        println!(
+            "{}",
+            json!({"event": "prove", "proved": proof.is_some(), "proof": proof})
+        );
+        return;
+    }
+    match proof {
+        Some(proof) => {
+            for line in backward::render(&proof) {
+                println!("  {}", line);
+            }
+        }
+        None => println!(
+            "\u{25cb} no proof within depth {} (fan-out {})",
+            config.max_depth, config.fan_out
+        ),
+    }
+}
+
+fn run_explain(space: &AtomSpace, spec: &str, json_mode: bool) {
+    let Some(proof) = explain::resolve(space, spec).and_then(|id| explain::explain(space, id))
+    else {
+        let e = format!("no atom '{}' (use a name or src->tgt)", spec);
+        if json_mode {
+            println!("{}", json!({"event": "explain", "error": e}));
+        } else {
+            println!("\u{2717} {}", e);
+        }
+        return;
+    };
+    if json_mode {
+        println!("{}", json!({"event": "explain", "proof": proof}));
+        return;
+    }
+    for line in explain::render(&proof) {
+        println!("  {}", line);
+    }
+}
+
+fn run_query(space: &AtomSpace, text: &str, json_mode: bool) {
+    let pattern = match Pattern::parse(text) {
+        Ok(p) => p,
+        Err(e) => {
+            if json_mode {
+                println!("{}", json!({"event": "query", "error": e.to_string()}));
+            } else {
+                println!("\u{2717} {}", e);
+            }
+            return;
+        }
+    };
+    let matches = pattern::find_matches(space, &pattern);
+    if json_mode {
+        let results: Vec<serde_json::Value> = matches
+            .iter()
+            .map(|m| {
+                m.bindings
+                    .iter()
+                    .map(|(var, &id)| (var.clone(), json!(space.format_atom(id))))
+                    .collect::<serde_json::Map<_, _>>()
+                    .into()
+            })
+            .collect();
+        println!(
+            "{}",
+            json!({"event": "query", "count": matches.len(), "matches": results})
+        );
+        return;
+    }
+    println!("? {} matches", matches.len());
+    for m in &matches {
+        let parts: Vec<String> = m
+            .bindings
+            .iter()
+            .map(|(var, &id)| format!("{}={}", var, space.format_atom(id)))
+            .collect();
+        println!("  {}", parts.join("  "));
+    }
+}
+
+fn run_import(space: &mut AtomSpace, path: &Path, json_mode: bool) {
+    let before = space.size();
+    let result = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| atomese::import(space, &text).map_err(|e| e.to_string()));
+    match (result, json_mode) {
+        (Ok(ids), true) => println!(
+            "{}",
+            json!({"event": "import", "expressions": ids.len(), "new_atoms": space.size() - before, "total_atoms": space.size()})
+        ),
+        (Ok(ids), false) => println!(
+            "\u{2295} Imported {} expressions ({} new atoms) from {}",
+            ids.len(),
+            space.size() - before,
+            path.display()
+        ),
+        (Err(e), true) => println!("{}", json!({"event": "import", "error": e})),
+        (Err(e), false) => println!("\u{2717} Import failed: {}", e),
+    }
+}
+
+fn run_export(space: &AtomSpace, path: Option<&Path>, json_mode: bool) {
+    let text = atomese::export(space);
+    let Some(path) = path else {
+        if json_mode {
+            println!("{}", json!({"event": "export", "atomese": text}));
+        } else {
+            print!("{}", text);
+        }
+        return;
+    };
+    match (std::fs::write(path, &text), json_mode) {
+        (Ok(()), true) => println!(
+            "{}",
+            json!({"event": "export", "path": path.display().to_string(), "total_atoms": space.size()})
+        ),
+        (Ok(()), false) => println!(
+            "\u{2913} Exported {} atoms to {}",
+            space.size(),
+            path.display()
+        ),
+        (Err(e), true) => println!("{}", json!({"event": "export", "error": e.to_string()})),
+        (Err(e), false) => println!("\u{2717} Export failed: {}", e),
+    }
+}
+
 // ── Human-readable output ──────────────────────────────────
 
 fn print_trace(r: &cogloop::CogLoopResult) {
@@ -159,11 +467,11 @@ fn print_atoms(space: &AtomSpace) {
     }
 }
 
-fn print_focus(space: &AtomSpace) {
-    println!("\u{2605} FOCUS (top STI atoms)");
-    let top = space.atoms_by_sti(15);
+fn print_focus(space: &AtomSpace, boundary: &FocusBoundary) {
+    println!("\u{2605} FOCUS ({})", boundary);
+    let top = boundary.select(space);
     if top.is_empty() {
-        println!("  (no atoms with STI > 0)");
+        println!("  (no atoms in focus)");
         return;
     }
     for atom in top {
@@ -183,9 +491,37 @@ fn print_types(space: &AtomSpace) {
     }
 }
 
-fn run_infer(space: &mut AtomSpace) {
-    println!("Running PLN forward chain (depth 3)...");
-    let inferences = pln::forward_chain(space, 3);
+/// Rules named on the `:infer` line; the enabled ones when none are given
+fn parse_rules<'a>(
+    registry: &'a RuleRegistry,
+    args: &[&str],
+) -> Result<Vec<&'a dyn InferenceRule>, String> {
+    if args.is_empty() {
+        return Ok(registry.enabled());
+    }
+    if args == ["all"] {
+        return Ok(registry.all());
+    }
+    args.iter()
+        .map(|&name| {
+            registry.get(name).ok_or_else(|| {
+                format!(
+                    "unknown rule '{}' ({} or all)",
+                    name,
+                    registry.names().join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+fn run_infer(space: &mut AtomSpace, rules: &[&dyn InferenceRule]) {
+    let names: Vec<_> = rules.iter().map(|r| r.name()).collect();
+    println!(
+        "Running PLN forward chain (depth 3, {})...",
+        names.join(", ")
+    );
+    let inferences = pln::forward_chain_incremental(space, 3, rules);
     println!("\u{22a2} {} inferences produced", inferences.len());
     for inf in &inferences {
         let name = space.format_atom(inf.conclusion_id);
@@ -195,14 +531,47 @@ fn run_infer(space: &mut AtomSpace) {
             .map(|&id| space.format_atom(id))
             .collect();
         println!(
-            "  \u{22a2} {} ({}) [{}]",
+            "  \u{22a2} {} ({}) {} [{}]",
             name,
             inf.tv,
+            inf.rule,
             premises.join(" + ")
         );
     }
 }
 
+fn parse_removal<'a>(args: &[&'a str]) -> Result<(&'a str, RemovalPolicy), String> {
+    let Some(&name) = args.first() else {
+        return Err("usage: :remove <name> [refuse|cascade|orphan]".into());
+    };
+    let policy = match args.get(1).copied() {
+        None | Some("refuse") => RemovalPolicy::Refuse,
+        Some("cascade") => RemovalPolicy::Cascade,
+        Some("orphan") => RemovalPolicy::Orphan,
+        Some(other) => return Err(format!("unknown removal policy '{}'", other)),
+    };
+    Ok((name, policy))
+}
+
+fn run_remove(space: &mut AtomSpace, args: &[&str]) {
+    let (name, policy) = match parse_removal(args) {
+        Ok(p) => p,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let Some(id) = space.find_named(name) else {
+        println!("No atom named \"{}\"", name);
+        return;
+    };
+    let label = space.format_atom(id);
+    match space.remove_atom(id, policy) {
+        Ok(removed) => println!("\u{2296} Removed {} ({} atoms)", label, removed.len()),
+        Err(e) => println!("\u{2717} Cannot remove {}: {}", label, e),
+    }
+}
+
 fn run_tikkun(space: &AtomSpace) {
     println!("Running tikkun diagnostics...");
     let report = tikkun::run_tikkun(space);
@@ -225,23 +594,39 @@ fn print_help() {
     println!("  \"cat is-a mammal\"   \u{2014} assert inheritance link");
     println!("  \"cat is a pet\"      \u{2014} assert inheritance link");
     println!("  \"cat likes fish\"    \u{2014} assert evaluation link");
+    println!("  \"cat is not a dog\"  \u{2014} assert a negated inheritance link");
+    println!("  \"wolf resembles dog\" \u{2014} assert similarity link");
+    println!("  \"tom is a member of cat\" \u{2014} assert instance-of (member link)");
+    println!("  \"mammal is warm-blooded\" \u{2014} assert a property (inherited by members)");
     println!();
     println!("Questions:");
-    println!("  \"what is cat\"       \u{2014} query (creates evaluation atoms)");
+    println!("  \"what is cat\"       \u{2014} answer from the AtomSpace (adds nothing)");
+    println!("  \"is cat a thing\"    \u{2014} prove by backward chaining (adds nothing)");
+    println!("  \"are all cats pets\" \u{2014} the same question about plurals");
+    println!("  \"what resembles a cat\" \u{2014} similar terms (adds nothing)");
     println!("  \"what can you do\"   \u{2014} query");
     println!();
     println!("Commands:");
     println!("  :atoms   \u{2014} show all atoms");
     println!("  :focus   \u{2014} show attention focus (top STI)");
     println!("  :types   \u{2014} show type counts");
-    println!("  :infer   \u{2014} run PLN forward chain");
+    println!("  :infer   \u{2014} run PLN forward chain (:infer induction abduction, :infer all)");
+    println!("  :remove  \u{2014} remove an atom (:remove cat cascade)");
     println!("  :tikkun  \u{2014} run diagnostics");
+    println!("  :save    \u{2014} write snapshot (:save [path])");
+    println!(
+        "  :query   \u{2014} pattern match (:query (InheritanceLink $X (ConceptNode \"mammal\")))"
+    );
+    println!("  :prove   \u{2014} prove a link on demand (:prove cat->living-thing)");
+    println!("  :explain \u{2014} why an atom holds (:explain cucumber->thing)");
+    println!("  :import  \u{2014} load Atomese (:import kb.scm)");
+    println!("  :export  \u{2014} print or write Atomese (:export [kb.scm])");
     println!("  :quit    \u{2014} exit");
 }
 
 // ── JSON output ────────────────────────────────────────────
 
-fn print_trace_json(r: &cogloop::CogLoopResult, space: &AtomSpace) {
+fn print_trace_json(r: &cogloop::CogLoopResult, space: &AtomSpace, boundary: &FocusBoundary) {
     let trace: Vec<serde_json::Value> = r
         .trace
         .iter()
@@ -253,8 +638,24 @@ fn print_trace_json(r: &cogloop::CogLoopResult, space: &AtomSpace) {
         })
         .collect();
 
-    let focus: Vec<serde_json::Value> = space
-        .atoms_by_sti(10)
+    let contradictions: Vec<serde_json::Value> = r
+        .contradictions
+        .iter()
+        .map(|c| {
+            let premises: Vec<String> =
+                c.premises.iter().map(|&id| space.format_atom(id)).collect();
+            json!({
+                "atom": space.format_atom(c.atom),
+                "stored": { "s": c.stored.strength, "c": c.stored.confidence },
+                "rule": c.rule,
+                "premises": premises,
+                "inferred": { "s": c.inferred.strength, "c": c.inferred.confidence },
+            })
+        })
+        .collect();
+
+    let focus: Vec<serde_json::Value> = boundary
+        .select(space)
         .iter()
         .map(|a| {
             json!({
@@ -265,6 +666,11 @@ fn print_trace_json(r: &cogloop::CogLoopResult, space: &AtomSpace) {
         })
         .collect();
 
+    let names =
+        |ids: &[AtomId]| -> Vec<String> { ids.iter().map(|&id| space.format_atom(id)).collect() };
+    let entered = names(&r.focus_shift.entered);
+    let left = names(&r.focus_shift.left);
+
     println!(
         "{}",
         json!({
@@ -275,6 +681,11 @@ fn print_trace_json(r: &cogloop::CogLoopResult, space: &AtomSpace) {
             "inferences": r.inferences,
             "trace": trace,
             "focus": focus,
+            "focus_shift": { "entered": entered, "left": left },
+            "answer": r.answer,
+            "contradictions": contradictions,
+            "forgotten": r.forgotten.iter().map(|f| &f.desc).collect::<Vec<_>>(),
+            "bank": space.bank(),
         })
     );
 }
@@ -300,9 +711,9 @@ fn print_atoms_json(space: &AtomSpace) {
     );
 }
 
-fn print_focus_json(space: &AtomSpace) {
-    let focus: Vec<serde_json::Value> = space
-        .atoms_by_sti(15)
+fn print_focus_json(space: &AtomSpace, boundary: &FocusBoundary) {
+    let focus: Vec<serde_json::Value> = boundary
+        .select(space)
         .iter()
         .map(|a| {
             json!({
@@ -312,7 +723,10 @@ fn print_focus_json(space: &AtomSpace) {
             })
         })
         .collect();
-    println!("{}", json!({"event": "focus", "atoms": focus}));
+    println!(
+        "{}",
+        json!({"event": "focus", "boundary": boundary.to_string(), "atoms": focus})
+    );
 }
 
 fn print_types_json(space: &AtomSpace) {
@@ -324,8 +738,8 @@ fn print_types_json(space: &AtomSpace) {
     println!("{}", json!({"event": "types", "types": types}));
 }
 
-fn run_infer_json(space: &mut AtomSpace) {
-    let inferences = pln::forward_chain(space, 3);
+fn run_infer_json(space: &mut AtomSpace, rules: &[&dyn InferenceRule]) {
+    let inferences = pln::forward_chain_incremental(space, 3, rules);
     let inf_json: Vec<serde_json::Value> = inferences
         .iter()
         .map(|inf| {
@@ -343,6 +757,30 @@ fn run_infer_json(space: &mut AtomSpace) {
     );
 }
 
+fn run_remove_json(space: &mut AtomSpace, args: &[&str]) {
+    let (name, policy) = match parse_removal(args) {
+        Ok(p) => p,
+        Err(e) => {
+            println!("{}", json!({"event": "remove", "error": e}));
+            return;
+        }
+    };
+    let Some(id) = space.find_named(name) else {
+        println!(
+            "{}",
+            json!({"event": "remove", "error": format!("no atom named '{}'", name)})
+        );
+        return;
+    };
+    match space.remove_atom(id, policy) {
+        Ok(removed) => println!(
+            "{}",
+            json!({"event": "remove", "removed": removed, "total_atoms": space.size()})
+        ),
+        Err(e) => println!("{}", json!({"event": "remove", "error": e.to_string()})),
+    }
+}
+
 fn run_tikkun_json(space: &AtomSpace) {
     let report = tikkun::run_tikkun(space);
     let checks: Vec<serde_json::Value> = report
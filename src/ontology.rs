@@ -1,66 +1,78 @@
 //! Base ontology loader — biological taxonomy for grounding
 
+use std::collections::HashSet;
+
 use crate::atom::*;
 use crate::atomspace::AtomSpace;
 
+const CONCEPTS: &[(&str, f64, f64)] = &[
+    ("thing", 0.99, 0.99),
+    ("living-thing", 0.95, 0.95),
+    ("non-living", 0.95, 0.95),
+    ("animal", 0.95, 0.90),
+    ("plant", 0.95, 0.90),
+    ("mammal", 0.95, 0.90),
+    ("fish", 0.95, 0.90),
+    ("bird", 0.95, 0.90),
+    ("vegetable", 0.95, 0.90),
+    ("cat", 0.90, 0.85),
+    ("dog", 0.90, 0.85),
+    ("eagle", 0.90, 0.85),
+    ("salmon", 0.90, 0.85),
+    ("tree", 0.90, 0.85),
+    ("flower", 0.90, 0.85),
+    ("cucumber", 0.90, 0.85),
+    ("can-fly", 0.90, 0.90),
+    ("can-swim", 0.90, 0.90),
+    ("warm-blooded", 0.90, 0.90),
+    ("cold-blooded", 0.90, 0.90),
+];
+
+const PREDICATES: &[(&str, f64, f64)] = &[
+    ("afraid-of", 0.80, 0.70),
+    ("resembles", 0.70, 0.50),
+    ("has-property", 0.90, 0.90),
+];
+
+/// Direct inheritance links only — PLN will derive the transitive closure
+const LINKS: &[(&str, &str, f64, f64)] = &[
+    ("living-thing", "thing", 0.99, 0.95),
+    ("non-living", "thing", 0.99, 0.95),
+    ("animal", "living-thing", 0.99, 0.95),
+    ("plant", "living-thing", 0.99, 0.95),
+    ("mammal", "animal", 0.95, 0.90),
+    ("fish", "animal", 0.95, 0.90),
+    ("bird", "animal", 0.95, 0.90),
+    ("vegetable", "plant", 0.95, 0.90),
+    ("cat", "mammal", 0.95, 0.90),
+    ("dog", "mammal", 0.95, 0.90),
+    ("eagle", "bird", 0.95, 0.90),
+    ("salmon", "fish", 0.95, 0.90),
+    ("tree", "plant", 0.95, 0.90),
+    ("flower", "plant", 0.95, 0.90),
+    ("cucumber", "vegetable", 0.90, 0.85),
+];
+
+/// Properties of whole classes; PLN passes them down to members
+const PROPERTIES: &[(&str, &str, f64, f64)] = &[
+    ("mammal", "warm-blooded", 0.95, 0.90),
+    ("bird", "warm-blooded", 0.95, 0.90),
+    ("bird", "can-fly", 0.90, 0.85),
+    ("fish", "can-swim", 0.95, 0.90),
+    ("fish", "cold-blooded", 0.90, 0.85),
+];
+
 pub fn load_base_ontology(space: &mut AtomSpace) -> usize {
     let initial = space.size();
 
-    // Concept nodes
-    let concepts: &[(&str, f64, f64)] = &[
-        ("thing", 0.99, 0.99),
-        ("living-thing", 0.95, 0.95),
-        ("non-living", 0.95, 0.95),
-        ("animal", 0.95, 0.90),
-        ("plant", 0.95, 0.90),
-        ("mammal", 0.95, 0.90),
-        ("fish", 0.95, 0.90),
-        ("bird", 0.95, 0.90),
-        ("vegetable", 0.95, 0.90),
-        ("cat", 0.90, 0.85),
-        ("dog", 0.90, 0.85),
-        ("eagle", 0.90, 0.85),
-        ("salmon", 0.90, 0.85),
-        ("tree", 0.90, 0.85),
-        ("flower", 0.90, 0.85),
-        ("cucumber", 0.90, 0.85),
-        ("can-fly", 0.90, 0.90),
-        ("can-swim", 0.90, 0.90),
-        ("warm-blooded", 0.90, 0.90),
-        ("cold-blooded", 0.90, 0.90),
-    ];
-
-    for &(name, s, c) in concepts {
+    for &(name, s, c) in CONCEPTS {
         space.add_node(AtomType::ConceptNode, name, TruthValue::new(s, c));
     }
-
-    // Predicate nodes
-    let predicates: &[(&str, f64, f64)] = &[("afraid-of", 0.80, 0.70), ("resembles", 0.70, 0.50)];
-
-    for &(name, s, c) in predicates {
+    for &(name, s, c) in PREDICATES {
         space.add_node(AtomType::PredicateNode, name, TruthValue::new(s, c));
     }
 
-    // Direct inheritance links only — PLN will derive the transitive closure
-    let links: &[(&str, &str, f64, f64)] = &[
-        ("living-thing", "thing", 0.99, 0.95),
-        ("non-living", "thing", 0.99, 0.95),
-        ("animal", "living-thing", 0.99, 0.95),
-        ("plant", "living-thing", 0.99, 0.95),
-        ("mammal", "animal", 0.95, 0.90),
-        ("fish", "animal", 0.95, 0.90),
-        ("bird", "animal", 0.95, 0.90),
-        ("vegetable", "plant", 0.95, 0.90),
-        ("cat", "mammal", 0.95, 0.90),
-        ("dog", "mammal", 0.95, 0.90),
-        ("eagle", "bird", 0.95, 0.90),
-        ("salmon", "fish", 0.95, 0.90),
-        ("tree", "plant", 0.95, 0.90),
-        ("flower", "plant", 0.95, 0.90),
-        ("cucumber", "vegetable", 0.90, 0.85),
-    ];
-
-    for &(src, tgt, s, c) in links {
+    for &(src, tgt, s, c) in LINKS {
         let src_id = space
             .find_node(AtomType::ConceptNode, src)
             .unwrap_or_else(|| panic!("ontology: missing concept '{}'", src));
@@ -74,5 +86,75 @@ pub fn load_base_ontology(space: &mut AtomSpace) -> usize {
         );
     }
 
+    let has_property = space
+        .find_node(AtomType::PredicateNode, "has-property")
+        .expect("ontology: has-property predicate");
+    for &(class, property, s, c) in PROPERTIES {
+        let class_id = space
+            .find_node(AtomType::ConceptNode, class)
+            .unwrap_or_else(|| panic!("ontology: missing concept '{}'", class));
+        let property_id = space
+            .find_node(AtomType::ConceptNode, property)
+            .unwrap_or_else(|| panic!("ontology: missing concept '{}'", property));
+        let (list, _) = space.add_link(
+            AtomType::ListLink,
+            vec![class_id, property_id],
+            TruthValue::new(0.0, 0.0),
+        );
+        space.add_link(
+            AtomType::EvaluationLink,
+            vec![has_property, list],
+            TruthValue::new(s, c),
+        );
+    }
+
     space.size() - initial
 }
+
+/// Ids of the base ontology's atoms still in the space, e.g. to keep the
+/// forgetting agent away from them. Found by name, so a restored space
+/// protects the same atoms.
+pub fn base_atoms(space: &AtomSpace) -> HashSet<AtomId> {
+    let concept = |name| space.find_node(AtomType::ConceptNode, name);
+    let mut ids: HashSet<AtomId> = CONCEPTS.iter().filter_map(|&(n, ..)| concept(n)).collect();
+    ids.extend(
+        PREDICATES
+            .iter()
+            .filter_map(|&(n, ..)| space.find_node(AtomType::PredicateNode, n)),
+    );
+    for &(src, tgt, ..) in LINKS {
+        if let Some((s, t)) = concept(src).zip(concept(tgt)) {
+            ids.extend(space.find_link(AtomType::InheritanceLink, &[s, t]));
+        }
+    }
+    let has_property = space.find_node(AtomType::PredicateNode, "has-property");
+    for &(class, property, ..) in PROPERTIES {
+        let Some((c, p)) = concept(class).zip(concept(property)) else {
+            continue;
+        };
+        let Some(list) = space.find_link(AtomType::ListLink, &[c, p]) else {
+            continue;
+        };
+        ids.insert(list);
+        if let Some(pred) = has_property {
+            ids.extend(space.find_link(AtomType::EvaluationLink, &[pred, list]));
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_atoms_cover_the_loaded_ontology() {
+        let mut space = AtomSpace::new();
+        let loaded = load_base_ontology(&mut space);
+        crate::parse::parse_input(&mut space, "cat likes fish");
+        let base = base_atoms(&space);
+        assert_eq!(base.len(), loaded);
+        let likes = space.find_node(AtomType::PredicateNode, "likes").unwrap();
+        assert!(!base.contains(&likes));
+    }
+}
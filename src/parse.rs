@@ -11,8 +11,20 @@ pub struct ParsedAtom {
     pub is_new: bool,
 }
 
+/// A question recognized in the input; answered from the space, never stored
+#[derive(Debug, Clone, PartialEq)]
+pub enum Question {
+    /// "what is X" / "what is a X"
+    WhatIs(String),
+    /// "is X a Y" / "are (all) Xs Ys" — proved by backward chaining
+    IsA(String, String),
+    /// "what resembles X" / "what resembles a X"
+    Resembles(String),
+}
+
 pub struct ParseResult {
     pub atoms: Vec<ParsedAtom>,
+    pub question: Option<Question>,
 }
 
 impl ParseResult {
@@ -37,11 +49,92 @@ impl ParseResult {
 
 pub fn parse_input(space: &mut AtomSpace, input: &str) -> ParseResult {
     let input = input.trim().to_lowercase();
-    let input = input.trim_end_matches(|c: char| c == '?' || c == '!' || c == '.');
+    let input = input.trim_end_matches(['?', '!', '.']);
     let words: Vec<&str> = input.split_whitespace().collect();
 
     if words.is_empty() {
-        return ParseResult { atoms: Vec::new() };
+        return ParseResult {
+            atoms: Vec::new(),
+            question: None,
+        };
+    }
+
+    // Question: "what is X" / "what is a X" — a query, not an assertion
+    if words.len() >= 3 && words[0] == "what" && words[1] == "is" {
+        let rest = match words[2] {
+            "a" | "an" if words.len() >= 4 => &words[3..],
+            _ => &words[2..],
+        };
+        return ParseResult {
+            atoms: Vec::new(),
+            question: Some(Question::WhatIs(rest.join("-"))),
+        };
+    }
+
+    // Question: "what resembles X" / "what resembles a X"
+    if words.len() >= 3 && words[0] == "what" && words[1] == "resembles" {
+        let rest = match words[2] {
+            "a" | "an" if words.len() >= 4 => &words[3..],
+            _ => &words[2..],
+        };
+        return ParseResult {
+            atoms: Vec::new(),
+            question: Some(Question::Resembles(rest.join("-"))),
+        };
+    }
+
+    // Question: "is (a/an/the) X a/an Y"
+    if words[0] == "is" {
+        let rest = match words.get(1) {
+            Some(&("a" | "an" | "the")) => &words[2..],
+            _ => &words[1..],
+        };
+        if let Some(pos) = rest.iter().position(|&w| w == "a" || w == "an") {
+            if pos > 0 && pos + 1 < rest.len() {
+                return ParseResult {
+                    atoms: Vec::new(),
+                    question: Some(Question::IsA(
+                        rest[..pos].join("-"),
+                        rest[pos + 1..].join("-"),
+                    )),
+                };
+            }
+        }
+    }
+
+    // Question: "are all Xs Ys" / "are Xs Ys"
+    let plurals = match words.as_slice() {
+        ["are", "all", x, y] => Some((*x, *y)),
+        ["are", x, y] if x.ends_with('s') => Some((*x, *y)),
+        _ => None,
+    };
+    if let Some((x, y)) = plurals {
+        return ParseResult {
+            atoms: Vec::new(),
+            question: Some(Question::IsA(singular(space, x), singular(space, y))),
+        };
+    }
+
+    // Pattern: "X resembles Y" / "X is similar to Y"
+    if let Some(pos) = words.iter().position(|&w| w == "resembles") {
+        if pos > 0 && pos + 1 < words.len() {
+            return make_similarity(space, &words[..pos].join("-"), &words[pos + 1..].join("-"));
+        }
+    }
+    if let Some(pos) = words.windows(3).position(|w| w == ["is", "similar", "to"]) {
+        if pos > 0 && pos + 3 < words.len() {
+            return make_similarity(space, &words[..pos].join("-"), &words[pos + 3..].join("-"));
+        }
+    }
+
+    // Pattern: "X is a member of Y" / "X is an instance of Y" / "X member-of Y"
+    if let Some((elem, set)) = membership(&words) {
+        return make_member(space, &elem, &set);
+    }
+
+    // Pattern: "X is not a/an Y" / "X isn't a/an Y" / "X is-not-a Y"
+    if let Some((subj, obj)) = negated_isa(&words) {
+        return make_inheritance(space, &subj, &obj, true);
     }
 
     // Pattern: "X is-a Y" / "X isa Y"
@@ -49,28 +142,19 @@ pub fn parse_input(space: &mut AtomSpace, input: &str) -> ParseResult {
         if pos > 0 && pos < words.len() - 1 {
             let subj = words[..pos].join("-");
             let obj = words[pos + 1..].join("-");
-            return make_inheritance(space, &subj, &obj);
+            return make_inheritance(space, &subj, &obj, false);
         }
     }
 
     // Pattern: "X is a/an Y"
     if let Some(pos) = words.iter().position(|&w| w == "is") {
-        if pos > 0
-            && pos + 2 <= words.len() - 1
-            && (words[pos + 1] == "a" || words[pos + 1] == "an")
-        {
+        if pos > 0 && pos + 2 < words.len() && (words[pos + 1] == "a" || words[pos + 1] == "an") {
             let subj = words[..pos].join("-");
             let obj = words[pos + 2..].join("-");
-            return make_inheritance(space, &subj, &obj);
+            return make_inheritance(space, &subj, &obj, false);
         }
     }
 
-    // Question: "what is X"
-    if words.len() >= 3 && words[0] == "what" && words[1] == "is" {
-        let obj = words[2..].join("-");
-        return make_evaluation(space, "is", "what", &obj);
-    }
-
     // Question: "what can you X"
     if words.len() >= 4 && words[0] == "what" && words[1] == "can" && words[2] == "you" {
         let obj = words[3..].join("-");
@@ -85,10 +169,13 @@ pub fn parse_input(space: &mut AtomSpace, input: &str) -> ParseResult {
         return make_evaluation(space, pred, words[0], &obj);
     }
 
-    // Assertion: "X verb Y" (3+ words)
+    // Assertion: "X verb Y" (3+ words); "X is Y" states a property
     if words.len() >= 3 {
         let subj = words[0];
-        let pred = words[1];
+        let pred = match words[1] {
+            "is" => "has-property",
+            verb => verb,
+        };
         let obj = words[2..].join("-");
         return make_evaluation(space, pred, subj, &obj);
     }
@@ -96,15 +183,23 @@ pub fn parse_input(space: &mut AtomSpace, input: &str) -> ParseResult {
     // Two words: concepts + list
     if words.len() == 2 {
         let mut atoms = Vec::new();
-        let (id0, n0) =
-            space.add_node(AtomType::ConceptNode, words[0], TruthValue::new(0.80, 0.50));
+        let (id0, n0) = mention(
+            space,
+            AtomType::ConceptNode,
+            words[0],
+            TruthValue::new(0.80, 0.50),
+        );
         atoms.push(ParsedAtom {
             id: id0,
             desc: format!("ConceptNode \"{}\"", words[0]),
             is_new: n0,
         });
-        let (id1, n1) =
-            space.add_node(AtomType::ConceptNode, words[1], TruthValue::new(0.80, 0.50));
+        let (id1, n1) = mention(
+            space,
+            AtomType::ConceptNode,
+            words[1],
+            TruthValue::new(0.80, 0.50),
+        );
         atoms.push(ParsedAtom {
             id: id1,
             desc: format!("ConceptNode \"{}\"", words[1]),
@@ -120,69 +215,252 @@ pub fn parse_input(space: &mut AtomSpace, input: &str) -> ParseResult {
             desc: format!("ListLink [{}\u{2192}{}]", words[0], words[1]),
             is_new: ln,
         });
-        return ParseResult { atoms };
+        return ParseResult {
+            atoms,
+            question: None,
+        };
     }
 
     // Single word: concept
     let mut atoms = Vec::new();
-    let (id, is_new) = space.add_node(AtomType::ConceptNode, words[0], TruthValue::new(0.80, 0.50));
+    let (id, is_new) = mention(
+        space,
+        AtomType::ConceptNode,
+        words[0],
+        TruthValue::new(0.80, 0.50),
+    );
     atoms.push(ParsedAtom {
         id,
         desc: format!("ConceptNode \"{}\"", words[0]),
         is_new,
     });
-    ParseResult { atoms }
+    ParseResult {
+        atoms,
+        question: None,
+    }
+}
+
+/// A node named in the input. Naming a concept is not evidence about it,
+/// so an existing node keeps its truth value; `tv` only seeds a new one.
+fn mention(
+    space: &mut AtomSpace,
+    atom_type: AtomType,
+    name: &str,
+    tv: TruthValue,
+) -> (AtomId, bool) {
+    match space.find_node(atom_type, name) {
+        Some(id) => (id, false),
+        None => space.add_node(atom_type, name, tv),
+    }
+}
+
+/// The concept a plural names: the word itself if it is known, else the
+/// word without its trailing "s"
+fn singular(space: &AtomSpace, word: &str) -> String {
+    match word.strip_suffix('s') {
+        Some(stem) if space.find_node(AtomType::ConceptNode, word).is_none() => stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// Subject and object of a negated is-a statement
+fn negated_isa(words: &[&str]) -> Option<(String, String)> {
+    let article = |w: &str| w == "a" || w == "an";
+    for (pos, &w) in words.iter().enumerate() {
+        let obj_at = match w {
+            "is-not-a" | "isnt-a" => pos + 1,
+            "isn't" | "isnt" if words.get(pos + 1).is_some_and(|w| article(w)) => pos + 2,
+            "is" if words.get(pos + 1) == Some(&"not")
+                && words.get(pos + 2).is_some_and(|w| article(w)) =>
+            {
+                pos + 3
+            }
+            _ => continue,
+        };
+        if pos > 0 && obj_at < words.len() {
+            return Some((words[..pos].join("-"), words[obj_at..].join("-")));
+        }
+    }
+    None
+}
+
+/// Element and concept of an instance-of statement
+fn membership(words: &[&str]) -> Option<(String, String)> {
+    for (pos, &w) in words.iter().enumerate() {
+        let set_at = match w {
+            "member-of" | "instance-of" => pos + 1,
+            "is" if matches!(words.get(pos + 1), Some(&"a" | &"an"))
+                && matches!(words.get(pos + 2), Some(&"member" | &"instance"))
+                && words.get(pos + 3) == Some(&"of") =>
+            {
+                pos + 4
+            }
+            _ => continue,
+        };
+        if pos > 0 && set_at < words.len() {
+            return Some((words[..pos].join("-"), words[set_at..].join("-")));
+        }
+    }
+    None
+}
+
+fn make_member(space: &mut AtomSpace, elem: &str, set: &str) -> ParseResult {
+    let mut atoms = Vec::new();
+    let mut ids = Vec::new();
+    for name in [elem, set] {
+        let (id, is_new) = mention(
+            space,
+            AtomType::ConceptNode,
+            name,
+            TruthValue::new(0.90, 0.85),
+        );
+        atoms.push(ParsedAtom {
+            id,
+            desc: format!("ConceptNode \"{}\"", name),
+            is_new,
+        });
+        ids.push(id);
+    }
+
+    let (lid, ln) = space.add_link(AtomType::MemberLink, ids, TruthValue::new(0.95, 0.90));
+    atoms.push(ParsedAtom {
+        id: lid,
+        desc: format!("MemberLink [{}\u{2208}{}]", elem, set),
+        is_new: ln,
+    });
+
+    ParseResult {
+        atoms,
+        question: None,
+    }
 }
 
-fn make_inheritance(space: &mut AtomSpace, subj: &str, obj: &str) -> ParseResult {
+/// A negated link is as confident as an asserted one, but nearly false.
+/// Stating the opposite of a confident link corrects it: the new value
+/// replaces the old one instead of revising both toward 0.5.
+fn make_inheritance(space: &mut AtomSpace, subj: &str, obj: &str, negated: bool) -> ParseResult {
     let mut atoms = Vec::new();
 
-    let (sid, sn) = space.add_node(AtomType::ConceptNode, subj, TruthValue::new(0.90, 0.85));
+    let (sid, sn) = mention(
+        space,
+        AtomType::ConceptNode,
+        subj,
+        TruthValue::new(0.90, 0.85),
+    );
     atoms.push(ParsedAtom {
         id: sid,
         desc: format!("ConceptNode \"{}\"", subj),
         is_new: sn,
     });
 
-    let (oid, on) = space.add_node(AtomType::ConceptNode, obj, TruthValue::new(0.90, 0.85));
+    let (oid, on) = mention(
+        space,
+        AtomType::ConceptNode,
+        obj,
+        TruthValue::new(0.90, 0.85),
+    );
     atoms.push(ParsedAtom {
         id: oid,
         desc: format!("ConceptNode \"{}\"", obj),
         is_new: on,
     });
 
-    let (lid, ln) = space.add_link(
-        AtomType::InheritanceLink,
-        vec![sid, oid],
-        TruthValue::new(0.95, 0.90),
-    );
+    let (tv, arrow) = if negated {
+        (TruthValue::new(0.05, 0.90), "\u{219b}")
+    } else {
+        (TruthValue::new(0.95, 0.90), "\u{2192}")
+    };
+    let prior = space
+        .find_link(AtomType::InheritanceLink, &[sid, oid])
+        .and_then(|id| space.get(id))
+        .map(|a| a.tv);
+    let (lid, ln) = space.add_link(AtomType::InheritanceLink, vec![sid, oid], tv);
+    let mut desc = format!("InheritanceLink [{}{}{}]", subj, arrow, obj);
+    if let Some(old) = prior
+        .filter(|old| old.confidence >= 0.5 && (old.strength - 0.5) * (tv.strength - 0.5) < 0.0)
+    {
+        space.set_tv(lid, tv);
+        desc.push_str(&format!(
+            " corrects (stv {:.2}/{:.2})",
+            old.strength, old.confidence
+        ));
+    }
     atoms.push(ParsedAtom {
         id: lid,
-        desc: format!("InheritanceLink [{}\u{2192}{}]", subj, obj),
+        desc,
         is_new: ln,
     });
 
-    ParseResult { atoms }
+    ParseResult {
+        atoms,
+        question: None,
+    }
+}
+
+fn make_similarity(space: &mut AtomSpace, a: &str, b: &str) -> ParseResult {
+    let mut atoms = Vec::new();
+    let mut ids = Vec::new();
+    for name in [a, b] {
+        let (id, is_new) = mention(
+            space,
+            AtomType::ConceptNode,
+            name,
+            TruthValue::new(0.90, 0.85),
+        );
+        atoms.push(ParsedAtom {
+            id,
+            desc: format!("ConceptNode \"{}\"", name),
+            is_new,
+        });
+        ids.push(id);
+    }
+
+    let (lid, ln) = space.add_link(AtomType::SimilarityLink, ids, TruthValue::new(0.90, 0.90));
+    atoms.push(ParsedAtom {
+        id: lid,
+        desc: format!("SimilarityLink [{}\u{2194}{}]", a, b),
+        is_new: ln,
+    });
+
+    ParseResult {
+        atoms,
+        question: None,
+    }
 }
 
 fn make_evaluation(space: &mut AtomSpace, pred: &str, subj: &str, obj: &str) -> ParseResult {
     let mut atoms = Vec::new();
 
-    let (sid, sn) = space.add_node(AtomType::ConceptNode, subj, TruthValue::new(0.80, 0.50));
+    let (sid, sn) = mention(
+        space,
+        AtomType::ConceptNode,
+        subj,
+        TruthValue::new(0.80, 0.50),
+    );
     atoms.push(ParsedAtom {
         id: sid,
         desc: format!("ConceptNode \"{}\"", subj),
         is_new: sn,
     });
 
-    let (oid, on) = space.add_node(AtomType::ConceptNode, obj, TruthValue::new(0.80, 0.50));
+    let (oid, on) = mention(
+        space,
+        AtomType::ConceptNode,
+        obj,
+        TruthValue::new(0.80, 0.50),
+    );
     atoms.push(ParsedAtom {
         id: oid,
         desc: format!("ConceptNode \"{}\"", obj),
         is_new: on,
     });
 
-    let (pid, pn) = space.add_node(AtomType::PredicateNode, pred, TruthValue::new(0.70, 0.40));
+    let (pid, pn) = mention(
+        space,
+        AtomType::PredicateNode,
+        pred,
+        TruthValue::new(0.70, 0.40),
+    );
     atoms.push(ParsedAtom {
         id: pid,
         desc: format!("PredicateNode \"{}\"", pred),
@@ -211,7 +489,10 @@ fn make_evaluation(space: &mut AtomSpace, pred: &str, subj: &str, obj: &str) ->
         is_new: en,
     });
 
-    ParseResult { atoms }
+    ParseResult {
+        atoms,
+        question: None,
+    }
 }
 
 #[cfg(test)]
@@ -252,6 +533,48 @@ mod tests {
             .is_some());
     }
 
+    #[test]
+    fn parse_negated_isa() {
+        for input in [
+            "penguin is not a flier",
+            "penguin isn't a flier",
+            "penguin is-not-a flier",
+        ] {
+            let mut s = AtomSpace::new();
+            let r = parse_input(&mut s, input);
+            assert!(r.question.is_none());
+            let penguin = s.find_node(AtomType::ConceptNode, "penguin").unwrap();
+            let flier = s.find_node(AtomType::ConceptNode, "flier").unwrap();
+            let link = s
+                .find_link(AtomType::InheritanceLink, &[penguin, flier])
+                .unwrap_or_else(|| panic!("no link for {:?}", input));
+            let tv = s.get(link).unwrap().tv;
+            assert!(tv.strength < 0.1 && tv.confidence >= 0.9, "{:?}", input);
+            assert!(s.get_by_type(AtomType::EvaluationLink).is_empty());
+        }
+    }
+
+    #[test]
+    fn negation_replaces_an_assertion() {
+        let mut s = AtomSpace::new();
+        parse_input(&mut s, "whale is-a fish");
+        let r = parse_input(&mut s, "whale is not an fish");
+        assert!(r.atoms[2].desc.contains("corrects (stv 0.95/0.90)"));
+        let whale = s.find_node(AtomType::ConceptNode, "whale").unwrap();
+        let fish = s.find_node(AtomType::ConceptNode, "fish").unwrap();
+        let link = s
+            .find_link(AtomType::InheritanceLink, &[whale, fish])
+            .unwrap();
+        let tv = s.get(link).unwrap().tv;
+        assert_eq!(tv, TruthValue::new(0.05, 0.90), "not averaged to unsure");
+
+        // Correcting back works the same way; agreeing still revises
+        parse_input(&mut s, "whale is-a fish");
+        assert_eq!(s.get(link).unwrap().tv, TruthValue::new(0.95, 0.90));
+        parse_input(&mut s, "whale is-a fish");
+        assert!(s.get(link).unwrap().tv.confidence > 0.9);
+    }
+
     #[test]
     fn parse_evaluation() {
         let mut s = AtomSpace::new();
@@ -262,14 +585,103 @@ mod tests {
         assert!(s.find_node(AtomType::PredicateNode, "likes").is_some());
     }
 
+    #[test]
+    fn parse_property() {
+        let mut s = AtomSpace::new();
+        parse_input(&mut s, "whale is warm-blooded");
+        assert!(s
+            .find_node(AtomType::PredicateNode, "has-property")
+            .is_some());
+        assert!(s.find_node(AtomType::PredicateNode, "is").is_none());
+    }
+
     #[test]
     fn parse_what_is() {
         let mut s = AtomSpace::new();
         let r = parse_input(&mut s, "what is that");
-        assert_eq!(r.new_count(), 5);
-        assert!(s.find_node(AtomType::ConceptNode, "what").is_some());
-        assert!(s.find_node(AtomType::ConceptNode, "that").is_some());
-        assert!(s.find_node(AtomType::PredicateNode, "is").is_some());
+        assert_eq!(r.question, Some(Question::WhatIs("that".into())));
+        assert!(r.atoms.is_empty());
+        assert_eq!(s.size(), 0, "questions must not create atoms");
+        assert!(s.find_node(AtomType::ConceptNode, "what").is_none());
+    }
+
+    #[test]
+    fn parse_what_is_a() {
+        let mut s = AtomSpace::new();
+        let r = parse_input(&mut s, "What is a flying fish?");
+        assert_eq!(r.question, Some(Question::WhatIs("flying-fish".into())));
+        assert_eq!(s.size(), 0);
+    }
+
+    #[test]
+    fn parse_is_a_question() {
+        let mut s = AtomSpace::new();
+        let r = parse_input(&mut s, "Is cat a living thing?");
+        assert_eq!(
+            r.question,
+            Some(Question::IsA("cat".into(), "living-thing".into()))
+        );
+        for q in ["is a cat an animal?", "is the cat an animal"] {
+            let r = parse_input(&mut s, q);
+            assert_eq!(
+                r.question,
+                Some(Question::IsA("cat".into(), "animal".into())),
+                "{}",
+                q
+            );
+        }
+        assert_eq!(s.size(), 0);
+    }
+
+    #[test]
+    fn parse_are_all_question() {
+        let mut s = AtomSpace::new();
+        s.add_node(AtomType::ConceptNode, "glass", TruthValue::new(0.9, 0.8));
+        let r = parse_input(&mut s, "are all mammals cats?");
+        assert_eq!(
+            r.question,
+            Some(Question::IsA("mammal".into(), "cat".into()))
+        );
+        let r = parse_input(&mut s, "are all glass things");
+        assert_eq!(
+            r.question,
+            Some(Question::IsA("glass".into(), "thing".into())),
+            "known words are kept"
+        );
+        let r = parse_input(&mut s, "are cats mammals?");
+        assert_eq!(
+            r.question,
+            Some(Question::IsA("cat".into(), "mammal".into()))
+        );
+        assert_eq!(s.size(), 1);
+    }
+
+    #[test]
+    fn parse_resembles() {
+        let mut s = AtomSpace::new();
+        let r = parse_input(&mut s, "wolf resembles dog");
+        assert_eq!(r.new_count(), 3);
+        let r = parse_input(&mut s, "dog is similar to wolf");
+        assert_eq!(r.new_count(), 0, "same similarity either way round");
+        assert_eq!(s.get_by_type(AtomType::SimilarityLink).len(), 1);
+
+        let r = parse_input(&mut s, "what resembles a wolf?");
+        assert_eq!(r.question, Some(Question::Resembles("wolf".into())));
+    }
+
+    #[test]
+    fn parse_membership() {
+        let mut s = AtomSpace::new();
+        let r = parse_input(&mut s, "tom is an instance of cat");
+        assert_eq!(r.new_count(), 3);
+        let tom = s.find_node(AtomType::ConceptNode, "tom").unwrap();
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        assert!(s.find_link(AtomType::MemberLink, &[tom, cat]).is_some());
+        assert!(s.get_by_type(AtomType::InheritanceLink).is_empty());
+
+        parse_input(&mut s, "felix is a member of cat");
+        parse_input(&mut s, "garfield member-of cat");
+        assert_eq!(s.get_by_type(AtomType::MemberLink).len(), 3);
     }
 
     #[test]
@@ -288,11 +700,26 @@ mod tests {
         assert_eq!(r.new_count(), 2);
     }
 
+    #[test]
+    fn mentions_leave_node_truth_values_alone() {
+        let mut s = AtomSpace::new();
+        let (cat, _) = s.add_node(AtomType::ConceptNode, "cat", TruthValue::new(0.9, 0.85));
+        for input in ["cat is-a pet", "cat likes fish", "cat", "cat resembles dog"] {
+            parse_input(&mut s, input);
+        }
+        assert_eq!(s.get(cat).unwrap().tv, TruthValue::new(0.9, 0.85));
+        // Re-asserting a link still pools the evidence
+        let r = parse_input(&mut s, "cat is-a pet");
+        assert!(s.get(r.atoms[2].id).unwrap().tv.confidence > 0.9);
+    }
+
     #[test]
     fn strips_punctuation() {
         let mut s = AtomSpace::new();
-        parse_input(&mut s, "what is that?");
-        assert!(s.find_node(AtomType::ConceptNode, "that").is_some());
+        parse_input(&mut s, "cat likes fish!");
+        assert!(s.find_node(AtomType::ConceptNode, "fish").is_some());
+        let r = parse_input(&mut s, "what is that?");
+        assert_eq!(r.question, Some(Question::WhatIs("that".into())));
     }
 
     #[test]
@@ -0,0 +1,802 @@
+//! Pattern matcher — variable queries over the hypergraph
+//! A pattern is a list of clauses joined on shared variables, e.g.
+//! `InheritanceLink($X, mammal) ∧ EvaluationLink(likes, ListLink($X, $Y))`.
+//! Matching walks the incoming index from whatever is already grounded,
+//! so joins cost proportional to the neighbourhood, not the whole space.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::atom::*;
+use crate::atomese::{self, AtomeseError, SExpr};
+use crate::atomspace::AtomSpace;
+
+/// A pattern term: a variable, a concrete atom, or a structure to match
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Term {
+    Var(String),
+    Atom(AtomId),
+    Node(AtomType, String),
+    Link(AtomType, Vec<Term>),
+}
+
+impl Term {
+    pub fn var(name: &str) -> Self {
+        Term::Var(name.to_string())
+    }
+
+    pub fn concept(name: &str) -> Self {
+        Term::Node(AtomType::ConceptNode, name.to_string())
+    }
+
+    pub fn predicate(name: &str) -> Self {
+        Term::Node(AtomType::PredicateNode, name.to_string())
+    }
+
+    pub fn link(atom_type: AtomType, outgoing: Vec<Term>) -> Self {
+        Term::Link(atom_type, outgoing)
+    }
+
+    pub fn inheritance(a: Term, b: Term) -> Self {
+        Term::Link(AtomType::InheritanceLink, vec![a, b])
+    }
+
+    pub fn similarity(a: Term, b: Term) -> Self {
+        Term::Link(AtomType::SimilarityLink, vec![a, b])
+    }
+
+    /// Replace bound variables with the atoms they are bound to. Members
+    /// of unordered links are sorted, so A↔B and B↔A give the same term.
+    pub fn substitute(&self, bindings: &Bindings) -> Term {
+        match self {
+            Term::Var(v) => bindings
+                .get(v)
+                .map_or_else(|| self.clone(), |&id| Term::Atom(id)),
+            Term::Link(t, args) => {
+                let mut args: Vec<Term> = args.iter().map(|a| a.substitute(bindings)).collect();
+                if t.is_unordered() {
+                    args.sort();
+                }
+                Term::Link(*t, args)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Variable names in order of first appearance
+    pub fn vars(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        self.collect_vars(&mut out);
+        out
+    }
+
+    fn collect_vars<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Term::Var(v) if !out.contains(&v.as_str()) => out.push(v),
+            Term::Link(_, args) => args.iter().for_each(|a| a.collect_vars(out)),
+            _ => {}
+        }
+    }
+}
+
+pub type Bindings = BTreeMap<String, AtomId>;
+
+/// One solution: variable bindings plus the atom matched by each clause
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub bindings: Bindings,
+    pub clause_atoms: Vec<AtomId>,
+}
+
+/// Clauses that must all match, joined on shared variable names
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pattern {
+    pub clauses: Vec<Term>,
+    /// Variables restricted to one of the listed atom types
+    pub constraints: HashMap<String, Vec<AtomType>>,
+}
+
+impl Pattern {
+    pub fn new(clauses: Vec<Term>) -> Self {
+        Self {
+            clauses,
+            constraints: HashMap::new(),
+        }
+    }
+
+    pub fn with_type(mut self, var: &str, types: &[AtomType]) -> Self {
+        self.constraints.insert(var.to_string(), types.to_vec());
+        self
+    }
+
+    /// Parse a pattern written in Atomese. Variables are written as
+    /// `(VariableNode "$X")` or a bare `$X`; type constraints as
+    /// `(TypedVariableLink (VariableNode "$X") (TypeNode "ConceptNode"))`.
+    /// Every other top-level expression is a clause. A clause that is a
+    /// bare variable is rejected: it would range over the whole space.
+    pub fn parse(text: &str) -> Result<Self, AtomeseError> {
+        let mut pattern = Pattern::default();
+        for expr in atomese::read_sexprs(text)? {
+            if let Some((var, types)) = typed_variable(&expr)? {
+                pattern.constraints.entry(var).or_default().extend(types);
+            } else {
+                let clause = expr_to_term(&expr)?;
+                if let Term::Var(v) = &clause {
+                    return parse_err(
+                        expr.line(),
+                        format!("clause {} is a bare variable; give it a link or node", v),
+                    );
+                }
+                pattern.clauses.push(clause);
+            }
+        }
+        if pattern.clauses.is_empty() {
+            return Err(AtomeseError {
+                line: 1,
+                msg: "pattern has no clauses".into(),
+            });
+        }
+        Ok(pattern)
+    }
+
+    /// Variable names in order of first appearance across clauses
+    pub fn vars(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        for clause in &self.clauses {
+            clause.collect_vars(&mut out);
+        }
+        out
+    }
+}
+
+fn parse_err<T>(line: usize, msg: impl Into<String>) -> Result<T, AtomeseError> {
+    Err(AtomeseError {
+        line,
+        msg: msg.into(),
+    })
+}
+
+fn head_and_string(items: &[SExpr]) -> Option<(&str, &str)> {
+    match items {
+        [SExpr::Symbol(head, _), SExpr::Str(s, _)] => Some((head.as_str(), s.as_str())),
+        _ => None,
+    }
+}
+
+fn typed_variable(expr: &SExpr) -> Result<Option<(String, Vec<AtomType>)>, AtomeseError> {
+    let SExpr::List(items, line) = expr else {
+        return Ok(None);
+    };
+    match items.first() {
+        Some(SExpr::Symbol(h, _)) if h == "TypedVariableLink" || h == "TypedVariable" => {}
+        _ => return Ok(None),
+    }
+    let Some(Term::Var(var)) = items.get(1).map(expr_to_term).transpose()? else {
+        return parse_err(*line, "TypedVariableLink needs a variable first");
+    };
+    let mut types = Vec::new();
+    for item in &items[2..] {
+        let SExpr::List(inner, l) = item else {
+            return parse_err(item.line(), "expected (TypeNode \"...\")");
+        };
+        match head_and_string(inner) {
+            Some(("TypeNode" | "Type", name)) => match AtomType::from_name(name) {
+                Some(t) => types.push(t),
+                None => return parse_err(*l, format!("unknown atom type '{}'", name)),
+            },
+            _ => return parse_err(*l, "expected (TypeNode \"...\")"),
+        }
+    }
+    if types.is_empty() {
+        return parse_err(*line, "TypedVariableLink needs at least one TypeNode");
+    }
+    Ok(Some((var, types)))
+}
+
+fn expr_to_term(expr: &SExpr) -> Result<Term, AtomeseError> {
+    match expr {
+        SExpr::Symbol(s, _) if s.starts_with('$') => Ok(Term::Var(s.clone())),
+        SExpr::Symbol(s, l) => parse_err(*l, format!("unexpected symbol '{}'", s)),
+        SExpr::Str(_, l) => parse_err(*l, "unexpected string"),
+        SExpr::List(items, line) => {
+            if let Some(("VariableNode" | "Variable", name)) = head_and_string(items) {
+                return Ok(Term::Var(name.to_string()));
+            }
+            let Some(SExpr::Symbol(head, _)) = items.first() else {
+                return parse_err(*line, "expected an atom type");
+            };
+            let Some(atom_type) = AtomType::from_name(head) else {
+                return parse_err(*line, format!("unknown atom type '{}'", head));
+            };
+            if atom_type.is_node() {
+                match head_and_string(items) {
+                    Some((_, name)) => Ok(Term::Node(atom_type, name.to_string())),
+                    None => parse_err(*line, format!("{} needs exactly one name", atom_type)),
+                }
+            } else {
+                let args = items[1..]
+                    .iter()
+                    .map(expr_to_term)
+                    .collect::<Result<_, _>>()?;
+                Ok(Term::Link(atom_type, args))
+            }
+        }
+    }
+}
+
+/// Result of trying to resolve a term to a single atom
+enum Ground {
+    Id(AtomId),
+    /// Fully specified, but no such atom exists
+    Missing,
+    /// Contains unbound variables
+    Open,
+}
+
+fn ground(space: &AtomSpace, term: &Term, bindings: &Bindings) -> Ground {
+    match term {
+        Term::Var(v) => bindings.get(v).map_or(Ground::Open, |&id| Ground::Id(id)),
+        Term::Atom(id) => Ground::Id(*id),
+        Term::Node(t, name) => space
+            .find_node(*t, name)
+            .map_or(Ground::Missing, Ground::Id),
+        Term::Link(t, args) => {
+            let mut ids = Vec::with_capacity(args.len());
+            for arg in args {
+                match ground(space, arg, bindings) {
+                    Ground::Id(id) => ids.push(id),
+                    other => return other,
+                }
+            }
+            space
+                .find_link(*t, &ids)
+                .map_or(Ground::Missing, Ground::Id)
+        }
+    }
+}
+
+/// Candidate atoms for a clause, narrowed through the incoming index
+/// of any argument that is already grounded
+fn candidates(space: &AtomSpace, clause: &Term, bindings: &Bindings) -> Vec<AtomId> {
+    match ground(space, clause, bindings) {
+        Ground::Id(id) => return vec![id],
+        Ground::Missing => return Vec::new(),
+        Ground::Open => {}
+    }
+    let Term::Link(t, args) = clause else {
+        // An unbound variable clause matches anything
+        return space.all_ids();
+    };
+    for (pos, arg) in args.iter().enumerate() {
+        match ground(space, arg, bindings) {
+            Ground::Id(anchor) => {
+                // An unordered link may hold the anchor in any position
+                let incoming = if t.is_unordered() {
+                    space.incoming_links(anchor)
+                } else {
+                    space.incoming_at(anchor, pos)
+                };
+                return incoming
+                    .iter()
+                    .copied()
+                    .filter(|&lid| {
+                        space
+                            .get(lid)
+                            .is_some_and(|l| l.atom_type == *t && l.outgoing.len() == args.len())
+                    })
+                    .collect();
+            }
+            Ground::Missing => return Vec::new(),
+            Ground::Open => {}
+        }
+    }
+    space.get_by_type(*t)
+}
+
+/// Every extension of `bindings` under which `term` denotes `id`. The
+/// members of an unordered link match as a multiset: each argument takes a
+/// different member, in any order, so A↔B binds A to either member.
+fn unify(
+    space: &AtomSpace,
+    pattern: &Pattern,
+    term: &Term,
+    id: AtomId,
+    bindings: &Bindings,
+) -> Vec<Bindings> {
+    if !has_unordered(term) {
+        let mut b = bindings.clone();
+        return if unify_ordered(space, pattern, term, id, &mut b) {
+            vec![b]
+        } else {
+            Vec::new()
+        };
+    }
+    let Some(atom) = space.get(id) else {
+        return Vec::new();
+    };
+    let Term::Link(t, args) = term else {
+        unreachable!("only links hold unordered links");
+    };
+    if atom.atom_type != *t || atom.outgoing.len() != args.len() {
+        return Vec::new();
+    }
+    if t.is_unordered() {
+        let mut out = Vec::new();
+        let mut used = vec![false; args.len()];
+        unify_unordered(
+            space,
+            pattern,
+            args,
+            &atom.outgoing,
+            &mut used,
+            bindings,
+            &mut out,
+        );
+        return out;
+    }
+    args.iter()
+        .zip(&atom.outgoing)
+        .fold(vec![bindings.clone()], |ways, (arg, &oid)| {
+            ways.iter()
+                .flat_map(|b| unify(space, pattern, arg, oid, b))
+                .collect()
+        })
+}
+
+fn has_unordered(term: &Term) -> bool {
+    match term {
+        Term::Link(t, args) => t.is_unordered() || args.iter().any(has_unordered),
+        _ => false,
+    }
+}
+
+/// `unify` for a term without unordered links, which matches at most one
+/// way; extends `bindings` in place
+fn unify_ordered(
+    space: &AtomSpace,
+    pattern: &Pattern,
+    term: &Term,
+    id: AtomId,
+    bindings: &mut Bindings,
+) -> bool {
+    let Some(atom) = space.get(id) else {
+        return false;
+    };
+    match term {
+        Term::Var(v) => {
+            if let Some(&bound) = bindings.get(v) {
+                return bound == id;
+            }
+            if let Some(types) = pattern.constraints.get(v) {
+                if !types.contains(&atom.atom_type) {
+                    return false;
+                }
+            }
+            bindings.insert(v.clone(), id);
+            true
+        }
+        Term::Atom(want) => *want == id,
+        Term::Node(t, name) => atom.atom_type == *t && atom.name.as_deref() == Some(name),
+        Term::Link(t, args) => {
+            atom.atom_type == *t
+                && atom.outgoing.len() == args.len()
+                && args
+                    .iter()
+                    .zip(&atom.outgoing)
+                    .all(|(arg, &oid)| unify_ordered(space, pattern, arg, oid, bindings))
+        }
+    }
+}
+
+/// Assign each of `args` to a different unused member, backtracking
+fn unify_unordered(
+    space: &AtomSpace,
+    pattern: &Pattern,
+    args: &[Term],
+    members: &[AtomId],
+    used: &mut [bool],
+    bindings: &Bindings,
+    out: &mut Vec<Bindings>,
+) {
+    let Some((arg, rest)) = args.split_first() else {
+        if !out.contains(bindings) {
+            out.push(bindings.clone());
+        }
+        return;
+    };
+    for (i, &member) in members.iter().enumerate() {
+        // Repeated members are interchangeable: try each value once
+        let repeat = (0..i).any(|j| !used[j] && members[j] == member);
+        if used[i] || repeat {
+            continue;
+        }
+        used[i] = true;
+        for b in unify(space, pattern, arg, member, bindings) {
+            unify_unordered(space, pattern, rest, members, used, &b, out);
+        }
+        used[i] = false;
+    }
+}
+
+/// Match the clauses listed in `order`, one per level; `clause_atoms`
+/// holds the atoms matched so far in that order
+fn search(
+    space: &AtomSpace,
+    pattern: &Pattern,
+    order: &[usize],
+    bindings: &Bindings,
+    clause_atoms: &mut Vec<AtomId>,
+    out: &mut Vec<Match>,
+) {
+    let Some(&idx) = order.get(clause_atoms.len()) else {
+        let mut atoms = vec![0; order.len()];
+        for (&i, &id) in order.iter().zip(clause_atoms.iter()) {
+            atoms[i] = id;
+        }
+        out.push(Match {
+            bindings: bindings.clone(),
+            clause_atoms: atoms,
+        });
+        return;
+    };
+    let clause = &pattern.clauses[idx];
+    for cand in candidates(space, clause, bindings) {
+        for b in unify(space, pattern, clause, cand, bindings) {
+            clause_atoms.push(cand);
+            search(space, pattern, order, &b, clause_atoms, out);
+            clause_atoms.pop();
+        }
+    }
+}
+
+/// Find every way the pattern's clauses can be matched simultaneously
+pub fn find_matches(space: &AtomSpace, pattern: &Pattern) -> Vec<Match> {
+    let order: Vec<usize> = (0..pattern.clauses.len()).collect();
+    let mut out = Vec::new();
+    search(
+        space,
+        pattern,
+        &order,
+        &Bindings::new(),
+        &mut Vec::new(),
+        &mut out,
+    );
+    out
+}
+
+/// Matches in which clause `clause` is matched by `atom`. The seed is
+/// unified first and the other clauses are joined from its bindings, so
+/// the cost follows the seed's neighbourhood.
+pub fn find_matches_with(
+    space: &AtomSpace,
+    pattern: &Pattern,
+    clause: usize,
+    atom: AtomId,
+) -> Vec<Match> {
+    let mut out = Vec::new();
+    let Some(term) = pattern.clauses.get(clause) else {
+        return out;
+    };
+    let order: Vec<usize> = std::iter::once(clause)
+        .chain((0..pattern.clauses.len()).filter(|&i| i != clause))
+        .collect();
+    for bindings in unify(space, pattern, term, atom, &Bindings::new()) {
+        search(space, pattern, &order, &bindings, &mut vec![atom], &mut out);
+    }
+    out
+}
+
+/// Bindings under which `term` denotes `atom`, if any
+pub fn unify_atom(space: &AtomSpace, term: &Term, atom: AtomId) -> Option<Bindings> {
+    unify(space, &Pattern::default(), term, atom, &Bindings::new())
+        .into_iter()
+        .next()
+}
+
+/// Matches of the pattern that extend `bindings`
+pub fn find_matches_from(space: &AtomSpace, pattern: &Pattern, bindings: &Bindings) -> Vec<Match> {
+    let order: Vec<usize> = (0..pattern.clauses.len()).collect();
+    let mut out = Vec::new();
+    search(space, pattern, &order, bindings, &mut Vec::new(), &mut out);
+    out
+}
+
+/// The atom a fully bound term denotes, if it is in the space
+pub fn lookup(space: &AtomSpace, term: &Term, bindings: &Bindings) -> Option<AtomId> {
+    match ground(space, term, bindings) {
+        Ground::Id(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// Add the atoms a term denotes under `bindings`: the outermost atom gets
+/// `tv`, anything nested that is missing gets the default truth value.
+/// Returns the outermost atom and whether it is new, or None if a variable
+/// is unbound or a link would break its type's signature.
+pub fn instantiate(
+    space: &mut AtomSpace,
+    term: &Term,
+    bindings: &Bindings,
+    tv: TruthValue,
+) -> Option<(AtomId, bool)> {
+    match term {
+        Term::Var(v) => bindings.get(v).map(|&id| (id, false)),
+        Term::Atom(id) => space.get(*id).map(|_| (*id, false)),
+        Term::Node(t, name) => Some(space.add_node(*t, name, tv)),
+        Term::Link(t, args) => {
+            let mut outgoing = Vec::with_capacity(args.len());
+            for arg in args {
+                let inner = match lookup(space, arg, bindings) {
+                    Some(id) => id,
+                    None => instantiate(space, arg, bindings, TruthValue::default_tv())?.0,
+                };
+                outgoing.push(inner);
+            }
+            space.try_add_link(*t, outgoing, tv).ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontology;
+    use crate::parse;
+
+    fn space() -> AtomSpace {
+        let mut s = AtomSpace::new();
+        ontology::load_base_ontology(&mut s);
+        s
+    }
+
+    fn names(s: &AtomSpace, matches: &[Match], var: &str) -> Vec<String> {
+        let mut v: Vec<_> = matches
+            .iter()
+            .map(|m| s.short_name(m.bindings[var]))
+            .collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn single_clause_variable() {
+        let s = space();
+        let p = Pattern::new(vec![Term::inheritance(
+            Term::var("X"),
+            Term::concept("mammal"),
+        )]);
+        let m = find_matches(&s, &p);
+        assert_eq!(names(&s, &m, "X"), vec!["cat", "dog"]);
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let mammal = s.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let cat_mammal = s.find_link(AtomType::InheritanceLink, &[cat, mammal]);
+        assert!(m.iter().any(|x| Some(x.clause_atoms[0]) == cat_mammal));
+    }
+
+    #[test]
+    fn join_on_shared_variable() {
+        let s = space();
+        // grandparents of cat: cat -> $Y -> $Z
+        let p = Pattern::new(vec![
+            Term::inheritance(Term::concept("cat"), Term::var("Y")),
+            Term::inheritance(Term::var("Y"), Term::var("Z")),
+        ]);
+        let m = find_matches(&s, &p);
+        assert_eq!(m.len(), 1);
+        assert_eq!(s.short_name(m[0].bindings["Z"]), "animal");
+        assert_eq!(m[0].clause_atoms.len(), 2);
+    }
+
+    #[test]
+    fn nested_evaluation_pattern() {
+        let mut s = space();
+        parse::parse_input(&mut s, "cat likes fish");
+        parse::parse_input(&mut s, "dog likes bones");
+        let p = Pattern::new(vec![Term::link(
+            AtomType::EvaluationLink,
+            vec![
+                Term::predicate("likes"),
+                Term::link(AtomType::ListLink, vec![Term::var("X"), Term::var("Y")]),
+            ],
+        )]);
+        let m = find_matches(&s, &p);
+        assert_eq!(names(&s, &m, "X"), vec!["cat", "dog"]);
+        assert_eq!(names(&s, &m, "Y"), vec!["bones", "fish"]);
+    }
+
+    #[test]
+    fn type_constraints_filter_bindings() {
+        let mut s = space();
+        parse::parse_input(&mut s, "cat likes fish");
+        let p = Pattern::new(vec![Term::link(
+            AtomType::EvaluationLink,
+            vec![Term::var("P"), Term::var("L")],
+        )]);
+        assert_eq!(
+            find_matches(&s, &p).len(),
+            s.get_by_type(AtomType::EvaluationLink).len()
+        );
+        let only_concepts = p.clone().with_type("P", &[AtomType::ConceptNode]);
+        assert!(find_matches(&s, &only_concepts).is_empty());
+    }
+
+    #[test]
+    fn missing_constant_matches_nothing() {
+        let s = space();
+        let p = Pattern::new(vec![Term::inheritance(
+            Term::var("X"),
+            Term::concept("unicorn"),
+        )]);
+        assert!(find_matches(&s, &p).is_empty());
+    }
+
+    #[test]
+    fn repeated_variable_must_bind_same_atom() {
+        let mut s = AtomSpace::new();
+        let (a, _) = s.add_node(AtomType::ConceptNode, "a", TruthValue::new(0.9, 0.9));
+        let (b, _) = s.add_node(AtomType::ConceptNode, "b", TruthValue::new(0.9, 0.9));
+        s.add_link(
+            AtomType::InheritanceLink,
+            vec![a, b],
+            TruthValue::new(0.9, 0.9),
+        );
+        s.add_link(
+            AtomType::InheritanceLink,
+            vec![a, a],
+            TruthValue::new(0.9, 0.9),
+        );
+        let p = Pattern::new(vec![Term::inheritance(Term::var("X"), Term::var("X"))]);
+        let m = find_matches(&s, &p);
+        assert_eq!(m.len(), 1);
+        assert_eq!(m[0].bindings["X"], a);
+    }
+
+    #[test]
+    fn parse_atomese_pattern() {
+        let s = space();
+        let p = Pattern::parse(
+            r#"
+            (TypedVariableLink (VariableNode "$X") (TypeNode "ConceptNode"))
+            (InheritanceLink (VariableNode "$X") $Y)
+            (InheritanceLink $Y (ConceptNode "animal"))
+            "#,
+        )
+        .unwrap();
+        assert_eq!(p.vars(), vec!["$X", "$Y"]);
+        let m = find_matches(&s, &p);
+        assert_eq!(names(&s, &m, "$X"), vec!["cat", "dog", "eagle", "salmon"]);
+    }
+
+    #[test]
+    fn parse_rejects_bad_patterns() {
+        assert!(Pattern::parse("").is_err());
+        assert!(Pattern::parse("(InheritanceLink X $Y)").is_err());
+        assert!(
+            Pattern::parse("(TypedVariableLink $X (TypeNode \"Nope\")) (ListLink $X)").is_err()
+        );
+        // A bare variable clause would match every atom in the space
+        let err = Pattern::parse("$X $Y $Z").unwrap_err();
+        assert!(err.msg.contains("bare variable"), "{}", err.msg);
+        assert!(Pattern::parse("(ListLink $X) $X").is_err());
+    }
+
+    #[test]
+    fn seeded_match_fixes_one_clause() {
+        let s = space();
+        let p = Pattern::new(vec![
+            Term::inheritance(Term::var("A"), Term::var("B")),
+            Term::inheritance(Term::var("B"), Term::var("C")),
+        ]);
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let mammal = s.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let cm = s
+            .find_link(AtomType::InheritanceLink, &[cat, mammal])
+            .unwrap();
+        let first = find_matches_with(&s, &p, 0, cm);
+        assert_eq!(names(&s, &first, "C"), vec!["animal"]);
+        assert!(first.iter().all(|m| m.clause_atoms[0] == cm));
+        // As the second premise, cat→mammal needs a link into cat
+        assert!(find_matches_with(&s, &p, 1, cm).is_empty());
+        assert!(find_matches_with(&s, &p, 2, cm).is_empty());
+    }
+
+    #[test]
+    fn matches_extend_given_bindings() {
+        let s = space();
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let mammal = s.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let cm = s
+            .find_link(AtomType::InheritanceLink, &[cat, mammal])
+            .unwrap();
+        let isa = Term::inheritance(Term::var("A"), Term::var("B"));
+        let bindings = unify_atom(&s, &isa, cm).unwrap();
+        assert_eq!(bindings["A"], cat);
+        assert!(unify_atom(&s, &Term::concept("cat"), cm).is_none());
+
+        let p = Pattern::new(vec![Term::inheritance(Term::var("B"), Term::var("C"))]);
+        let m = find_matches_from(&s, &p, &bindings);
+        assert_eq!(names(&s, &m, "C"), vec!["animal"]);
+        assert!(m.iter().all(|m| m.bindings["A"] == cat));
+    }
+
+    #[test]
+    fn unordered_links_match_either_way_round() {
+        let mut s = space();
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let dog = s.find_node(AtomType::ConceptNode, "dog").unwrap();
+        let (sim, _) = s.add_link(
+            AtomType::SimilarityLink,
+            vec![dog, cat],
+            TruthValue::new(0.8, 0.8),
+        );
+        let p = Pattern::new(vec![Term::similarity(Term::var("A"), Term::var("B"))]);
+        let m = find_matches(&s, &p);
+        assert_eq!(names(&s, &m, "A"), vec!["cat", "dog"]);
+        let p = Pattern::new(vec![Term::similarity(Term::concept("cat"), Term::var("X"))]);
+        let m = find_matches(&s, &p);
+        assert_eq!(names(&s, &m, "X"), vec!["dog"]);
+        assert_eq!(find_matches_with(&s, &p, 0, sim).len(), 1);
+    }
+
+    #[test]
+    fn unordered_links_match_as_multisets() {
+        let mut s = space();
+        let ids: Vec<AtomId> = ["cat", "dog", "fish"]
+            .iter()
+            .map(|n| s.find_node(AtomType::ConceptNode, n).unwrap())
+            .collect();
+        s.add_link(
+            AtomType::AndLink,
+            vec![ids[2], ids[0], ids[1]],
+            TruthValue::new(0.9, 0.9),
+        );
+        let and = |args: Vec<Term>| Pattern::new(vec![Term::link(AtomType::AndLink, args)]);
+
+        // Every assignment of three variables to three members
+        let p = and(vec![Term::var("A"), Term::var("B"), Term::var("C")]);
+        assert_eq!(find_matches(&s, &p).len(), 6);
+
+        // Fixed members in any order leave the variable the remaining one
+        let p = and(vec![
+            Term::concept("fish"),
+            Term::var("X"),
+            Term::concept("cat"),
+        ]);
+        let m = find_matches(&s, &p);
+        assert_eq!(names(&s, &m, "X"), vec!["dog"]);
+        let p = and(vec![
+            Term::concept("cat"),
+            Term::concept("cat"),
+            Term::var("X"),
+        ]);
+        assert!(find_matches(&s, &p).is_empty(), "cat appears once");
+    }
+
+    #[test]
+    fn instantiate_builds_missing_structure() {
+        let mut s = space();
+        let term = Term::link(
+            AtomType::EvaluationLink,
+            vec![
+                Term::predicate("likes"),
+                Term::link(
+                    AtomType::ListLink,
+                    vec![Term::var("X"), Term::concept("fish")],
+                ),
+            ],
+        );
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let b = Bindings::from([("X".to_string(), cat)]);
+        assert!(instantiate(&mut s, &term, &Bindings::new(), TruthValue::default_tv()).is_none());
+        let (id, is_new) = instantiate(&mut s, &term, &b, TruthValue::new(0.9, 0.8)).unwrap();
+        assert!(is_new);
+        assert_eq!(lookup(&s, &term.substitute(&b), &Bindings::new()), Some(id));
+        assert_eq!(s.get(id).unwrap().tv, TruthValue::new(0.9, 0.8));
+
+        // NotLink takes one member
+        let ill = Term::link(
+            AtomType::NotLink,
+            vec![Term::var("X"), Term::concept("fish")],
+        );
+        assert!(instantiate(&mut s, &ill, &b, TruthValue::default_tv()).is_none());
+    }
+}
@@ -0,0 +1,463 @@
+//! Persistence — versioned AtomSpace snapshots on disk
+//! Snapshots are a single JSON document; see docs/persistence.md for the format.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::atom::*;
+use crate::atomspace::{check_signature, AtomSpace, AttentionBank, Provenance, SignatureError};
+use crate::ecan::Forgotten;
+
+/// Identifies a Coggy snapshot file
+pub const SNAPSHOT_FORMAT: &str = "coggy-atomspace";
+
+/// Version written by `save_snapshot`. Bump it whenever the layout changes
+/// and teach `migrate` how to lift the previous version.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Format(String),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "snapshot I/O error: {}", e),
+            SnapshotError::Format(msg) => write!(f, "malformed snapshot: {}", msg),
+            SnapshotError::UnsupportedVersion(v) => write!(
+                f,
+                "snapshot version {} is newer than supported version {}",
+                v, SNAPSHOT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(e: serde_json::Error) -> Self {
+        SnapshotError::Format(e.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    format: String,
+    version: u32,
+    turn: u32,
+    next_id: AtomId,
+    /// Since version 3: the attention bank's balance. Without it the bank
+    /// holds whatever the atoms don't.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bank: Option<AttentionBank>,
+    atoms: Vec<AtomRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AtomRecord {
+    id: AtomId,
+    #[serde(rename = "type")]
+    atom_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    outgoing: Vec<AtomId>,
+    tv: TvRecord,
+    av: AvRecord,
+    /// Since version 2: how an inferred atom was derived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    derived: Option<Provenance>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TvRecord {
+    s: f64,
+    c: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AvRecord {
+    sti: f64,
+    lti: f64,
+}
+
+fn build_snapshot(space: &AtomSpace) -> Snapshot {
+    let atoms = space
+        .all_atoms_sorted()
+        .into_iter()
+        .map(|a| AtomRecord {
+            id: a.id,
+            atom_type: a.atom_type.to_string(),
+            name: a.name.clone(),
+            outgoing: a.outgoing.clone(),
+            tv: TvRecord {
+                s: a.tv.strength,
+                c: a.tv.confidence,
+            },
+            av: AvRecord {
+                sti: a.av.sti,
+                lti: a.av.lti,
+            },
+            derived: space.provenance(a.id).cloned(),
+        })
+        .collect();
+    Snapshot {
+        format: SNAPSHOT_FORMAT.into(),
+        version: SNAPSHOT_VERSION,
+        turn: space.turn,
+        next_id: space.next_id(),
+        bank: Some(space.bank()),
+        atoms,
+    }
+}
+
+/// Serialize the whole space to a snapshot JSON value
+pub fn to_json(space: &AtomSpace) -> Value {
+    serde_json::to_value(build_snapshot(space)).expect("snapshot serializes")
+}
+
+/// Rebuild a space from a snapshot JSON value, migrating older versions
+pub fn from_json(mut value: Value) -> Result<AtomSpace, SnapshotError> {
+    let format = value.get("format").and_then(Value::as_str);
+    if format != Some(SNAPSHOT_FORMAT) {
+        return Err(SnapshotError::Format(format!(
+            "expected format \"{}\"",
+            SNAPSHOT_FORMAT
+        )));
+    }
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| SnapshotError::Format("missing version".into()))?;
+    let mut version = u32::try_from(version)
+        .map_err(|_| SnapshotError::Format(format!("version {} out of range", version)))?;
+    if version > SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+    while version < SNAPSHOT_VERSION {
+        value = migrate(value, version)?;
+        version += 1;
+    }
+
+    let snapshot: Snapshot = serde_json::from_value(value)?;
+    let mut seen = HashSet::new();
+    let mut atoms = Vec::with_capacity(snapshot.atoms.len());
+    let mut derived = Vec::new();
+    for rec in snapshot.atoms {
+        if !seen.insert(rec.id) {
+            return Err(SnapshotError::Format(format!(
+                "duplicate atom id {}",
+                rec.id
+            )));
+        }
+        let atom_type = AtomType::from_name(&rec.atom_type).ok_or_else(|| {
+            SnapshotError::Format(format!("unknown atom type \"{}\"", rec.atom_type))
+        })?;
+        let tv = TruthValue::new(rec.tv.s, rec.tv.c);
+        let mut atom = match (atom_type.is_node(), rec.name) {
+            (true, Some(name)) => Atom::new_node(rec.id, atom_type, &name, tv),
+            (false, None) => Atom::new_link(rec.id, atom_type, rec.outgoing, tv),
+            _ => {
+                return Err(SnapshotError::Format(format!(
+                    "atom {} has a name/outgoing mismatch for {}",
+                    rec.id, atom_type
+                )))
+            }
+        };
+        atom.av = AttentionValue {
+            sti: rec.av.sti,
+            lti: rec.av.lti,
+        };
+        atoms.push(atom);
+        if let Some(p) = rec.derived {
+            derived.push((rec.id, p));
+        }
+    }
+    let types: HashMap<AtomId, AtomType> = atoms.iter().map(|a| (a.id, a.atom_type)).collect();
+    for atom in atoms.iter().filter(|a| a.atom_type.is_link()) {
+        let members: Option<Vec<AtomType>> = atom
+            .outgoing
+            .iter()
+            .map(|id| types.get(id).copied())
+            .collect();
+        // A dangling member is tikkun's to report; only its arity can be checked
+        let checked = match members {
+            Some(members) => check_signature(atom.atom_type, &members),
+            None => match atom.atom_type.arity() {
+                Some(arity) if !arity.accepts(atom.outgoing.len()) => Err(SignatureError::Arity {
+                    atom_type: atom.atom_type,
+                    expected: arity,
+                    found: atom.outgoing.len(),
+                }),
+                _ => Ok(()),
+            },
+        };
+        checked.map_err(|e| SnapshotError::Format(format!("atom {}: {}", atom.id, e)))?;
+    }
+    let mut space = AtomSpace::from_parts(atoms, snapshot.next_id, snapshot.turn);
+    if let Some(bank) = snapshot.bank {
+        space.set_bank(bank);
+    }
+    for (id, p) in derived {
+        space.set_provenance(id, p);
+    }
+    Ok(space)
+}
+
+/// Lift a snapshot from `from_version` to `from_version + 1`
+fn migrate(mut value: Value, from_version: u32) -> Result<Value, SnapshotError> {
+    match from_version {
+        // 2 added the optional per-atom `derived` record; v1 atoms have none
+        1 => {
+            value["version"] = Value::from(2);
+            Ok(value)
+        }
+        // 3 added the attention bank; v2 spaces get the balance that
+        // conserves the funds
+        2 => {
+            value["version"] = Value::from(3);
+            Ok(value)
+        }
+        _ => Err(SnapshotError::Format(format!(
+            "no migration from snapshot version {}",
+            from_version
+        ))),
+    }
+}
+
+/// Write a snapshot atomically (temp file + rename). Both the file and the
+/// rename are synced before returning, so the snapshot survives a power
+/// loss once this succeeds.
+pub fn save_snapshot(space: &AtomSpace, path: &Path) -> Result<(), SnapshotError> {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty());
+    if let Some(dir) = dir {
+        fs::create_dir_all(dir)?;
+    }
+    // space.json.tmp: `with_extension` would clobber a neighbouring space.tmp
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(&serde_json::to_vec_pretty(&build_snapshot(space))?)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, path)?;
+    sync_dir(dir.unwrap_or(Path::new(".")))?;
+    Ok(())
+}
+
+/// Make a rename inside `dir` durable
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    fs::File::open(dir)?.sync_all()
+}
+
+/// Directories cannot be opened for syncing here; the rename is as durable
+/// as the platform makes it
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+pub fn load_snapshot(path: &Path) -> Result<AtomSpace, SnapshotError> {
+    let bytes = fs::read(path)?;
+    from_json(serde_json::from_slice(&bytes)?)
+}
+
+/// Append forgotten atoms to an Atomese archive, one per line, so `:import`
+/// can bring them back
+pub fn append_archive(path: &Path, forgotten: &[Forgotten]) -> io::Result<()> {
+    if forgotten.is_empty() {
+        return Ok(());
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    for f in forgotten.iter().filter(|f| !f.atomese.is_empty()) {
+        writeln!(file, "{}", f.atomese)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ontology;
+    use crate::pln;
+
+    fn learned_space() -> AtomSpace {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let cat = space.find_node(AtomType::ConceptNode, "cat").unwrap();
+        space.set_sti(cat, 12.5);
+        space.turn = 7;
+        pln::forward_chain(&mut space, 2);
+        space
+    }
+
+    #[test]
+    fn roundtrip_preserves_atoms_and_counters() {
+        let space = learned_space();
+        let restored = from_json(to_json(&space)).unwrap();
+        assert_eq!(restored.size(), space.size());
+        assert_eq!(restored.turn, 7);
+        assert_eq!(restored.next_id(), space.next_id());
+        for atom in space.all_atoms_sorted() {
+            let other = restored.get(atom.id).unwrap();
+            assert_eq!(other.atom_type, atom.atom_type);
+            assert_eq!(other.name, atom.name);
+            assert_eq!(other.outgoing, atom.outgoing);
+            assert_eq!(other.tv, atom.tv);
+            assert_eq!(other.av.sti, atom.av.sti);
+        }
+        assert_eq!(restored.bank(), space.bank());
+    }
+
+    #[test]
+    fn roundtrip_rebuilds_indexes() {
+        let space = learned_space();
+        let mut restored = from_json(to_json(&space)).unwrap();
+        let cat = restored.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let mammal = restored.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let link = restored
+            .find_link(AtomType::InheritanceLink, &[cat, mammal])
+            .unwrap();
+        assert!(restored.get_incoming(cat).contains(&link));
+        assert_eq!(
+            restored.get_by_type(AtomType::InheritanceLink).len(),
+            space.get_by_type(AtomType::InheritanceLink).len()
+        );
+        // New atoms continue from the stored id counter
+        let (id, is_new) =
+            restored.add_node(AtomType::ConceptNode, "ferret", TruthValue::new(0.9, 0.8));
+        assert!(is_new);
+        assert_eq!(id, space.next_id());
+    }
+
+    #[test]
+    fn save_and_load_file() {
+        let dir = std::env::temp_dir().join(format!("coggy-persist-{}", std::process::id()));
+        let path = dir.join("space.json");
+        let space = learned_space();
+        save_snapshot(&space, &path).unwrap();
+        let restored = load_snapshot(&path).unwrap();
+        assert_eq!(restored.size(), space.size());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn temp_file_keeps_the_full_name() {
+        let dir = std::env::temp_dir().join(format!("coggy-tmpname-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("space.json");
+        let neighbour = dir.join("space.tmp");
+        fs::write(&neighbour, "keep me").unwrap();
+        save_snapshot(&learned_space(), &path).unwrap();
+        assert_eq!(fs::read_to_string(&neighbour).unwrap(), "keep me");
+        assert!(!dir.join("space.json.tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_newer_version() {
+        let mut value = to_json(&learned_space());
+        value["version"] = serde_json::json!(SNAPSHOT_VERSION + 1);
+        assert!(matches!(
+            from_json(value),
+            Err(SnapshotError::UnsupportedVersion(_))
+        ));
+        // Not truncated into a small, supported version
+        value = to_json(&learned_space());
+        value["version"] = serde_json::json!(u64::from(u32::MAX) + 2);
+        assert!(matches!(from_json(value), Err(SnapshotError::Format(_))));
+    }
+
+    #[test]
+    fn roundtrip_preserves_provenance() {
+        let space = learned_space();
+        let restored = from_json(to_json(&space)).unwrap();
+        let derived: Vec<_> = space
+            .all_atoms_sorted()
+            .into_iter()
+            .filter_map(|a| space.provenance(a.id).map(|p| (a.id, p.clone())))
+            .collect();
+        assert!(!derived.is_empty());
+        for (id, p) in derived {
+            assert_eq!(restored.provenance(id), Some(&p));
+        }
+    }
+
+    #[test]
+    fn migrates_version_1() {
+        let mut value = to_json(&learned_space());
+        value["version"] = serde_json::json!(1);
+        value.as_object_mut().unwrap().remove("bank");
+        for atom in value["atoms"].as_array_mut().unwrap() {
+            atom.as_object_mut().unwrap().remove("derived");
+        }
+        let restored = from_json(value).unwrap();
+        assert_eq!(restored.size(), learned_space().size());
+        assert!(restored
+            .all_atoms_sorted()
+            .iter()
+            .all(|a| restored.provenance(a.id).is_none()));
+    }
+
+    #[test]
+    fn migrates_version_2_with_a_balanced_bank() {
+        use crate::atomspace::STI_FUNDS;
+        let space = learned_space();
+        let mut value = to_json(&space);
+        value["version"] = serde_json::json!(2);
+        value.as_object_mut().unwrap().remove("bank");
+        let restored = from_json(value).unwrap();
+        assert_eq!(restored.bank(), space.bank());
+        assert_eq!(restored.bank().sti, STI_FUNDS - 12.5);
+    }
+
+    #[test]
+    fn rejects_foreign_documents() {
+        let value = serde_json::json!({"version": 1, "atoms": []});
+        assert!(matches!(from_json(value), Err(SnapshotError::Format(_))));
+    }
+
+    #[test]
+    fn rejects_unknown_atom_type() {
+        let mut value = to_json(&learned_space());
+        value["atoms"][0]["type"] = serde_json::json!("MysteryNode");
+        assert!(matches!(from_json(value), Err(SnapshotError::Format(_))));
+    }
+
+    #[test]
+    fn rejects_ill_typed_links() {
+        let mut value = to_json(&learned_space());
+        let eval = value["atoms"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|a| a["type"] == "EvaluationLink")
+            .unwrap();
+        eval["outgoing"].as_array_mut().unwrap().pop();
+        let Err(SnapshotError::Format(msg)) = from_json(value) else {
+            panic!("a one-member EvaluationLink should be rejected");
+        };
+        assert!(msg.contains("exactly 2"), "{}", msg);
+    }
+}
@@ -1,8 +1,37 @@
 //! PLN — Probabilistic Logic Networks
-//! Forward-chaining inference on InheritanceLinks.
+//! Forward chaining: applies `rules::InferenceRule`s to links in the space.
+
+use std::collections::HashSet;
 
 use crate::atom::*;
-use crate::atomspace::AtomSpace;
+use crate::atomspace::{AtomSpace, Provenance};
+use crate::pattern::{self, Bindings, Term};
+use crate::rules::{Deduction, InferenceRule, RuleRegistry};
+
+/// Attention-guided inference: how many conclusions a single run may add,
+/// and which rules it may use. The focus itself is ECAN's
+/// (`ecan::FocusBoundary`), so ATTEND and INFER agree on what is attended.
+#[derive(Debug, Clone)]
+pub struct InferenceControl {
+    /// Maximum number of new conclusions per run
+    pub budget: usize,
+    /// Rules the cognitive loop chains with; only enabled ones fire
+    pub rules: RuleRegistry,
+}
+
+impl Default for InferenceControl {
+    fn default() -> Self {
+        Self {
+            budget: 16,
+            rules: RuleRegistry::builtin(),
+        }
+    }
+}
+
+/// Truth values this far apart in strength disagree about the link
+const CONTRADICTION_GAP: f64 = 0.5;
+/// Below this confidence either side is too unsure to contradict the other
+const CONTRADICTION_CONFIDENCE: f64 = 0.5;
 
 #[derive(Debug)]
 pub struct Inference {
@@ -12,93 +41,439 @@ pub struct Inference {
     pub tv: TruthValue,
 }
 
-/// PLN deduction truth value formula (simplified):
-///   strength:   s_ac = s_ab * s_bc
-///   confidence: c_ac = min(c_ab, c_bc) * 0.9
-fn deduction_tv(tv_ab: TruthValue, tv_bc: TruthValue) -> TruthValue {
+/// A rule, applied to premises in the space, concludes the opposite of
+/// what a stored atom says
+#[derive(Debug, Clone)]
+pub struct Contradiction {
+    /// The stored atom the rule disagrees with
+    pub atom: AtomId,
+    pub stored: TruthValue,
+    pub rule: String,
+    pub premises: Vec<AtomId>,
+    pub inferred: TruthValue,
+}
+
+/// Whether two confident truth values disagree on strength
+pub fn contradicts(a: TruthValue, b: TruthValue) -> bool {
+    a.confidence >= CONTRADICTION_CONFIDENCE
+        && b.confidence >= CONTRADICTION_CONFIDENCE
+        && (a.strength - b.strength).abs() >= CONTRADICTION_GAP
+}
+
+/// Whether P(Y|X) = s_xy is consistent with P(X) = s_x and P(Y) = s_y:
+///   max(0, (s_x + s_y − 1) / s_x) ≤ s_xy ≤ min(1, s_y / s_x)
+pub(crate) fn conditional_consistent(s_x: f64, s_y: f64, s_xy: f64) -> bool {
+    const EPS: f64 = 1e-9;
+    if s_x < EPS {
+        return true;
+    }
+    let lo = ((s_x + s_y - 1.0) / s_x).max(0.0);
+    let hi = (s_y / s_x).min(1.0);
+    s_xy >= lo - EPS && s_xy <= hi + EPS
+}
+
+/// Independence-based PLN deduction strength, A→B, B→C ⊢ A→C:
+///   s_ac = s_ab · s_bc + (1 − s_ab) · (s_c − s_b · s_bc) / (1 − s_b)
+/// Falls back to s_ab · s_bc when the premises are inconsistent with the
+/// node probabilities, and to s_c when B is (nearly) everything.
+fn deduction_strength(s_ab: f64, s_bc: f64, s_a: f64, s_b: f64, s_c: f64) -> f64 {
+    if !conditional_consistent(s_a, s_b, s_ab) || !conditional_consistent(s_b, s_c, s_bc) {
+        return s_ab * s_bc;
+    }
+    if s_b > 0.99 {
+        return s_c;
+    }
+    let off_b = ((s_c - s_b * s_bc) / (1.0 - s_b)).clamp(0.0, 1.0);
+    (s_ab * s_bc + (1.0 - s_ab) * off_b).clamp(0.0, 1.0)
+}
+
+/// Confidence of a two-premise conclusion from evidence counts:
+///   n = n_1 · n_2 / (n_1 + n_2 + K)
+/// always below the weaker premise, so long chains lose confidence.
+fn chained_confidence(tv_1: TruthValue, tv_2: TruthValue) -> f64 {
+    let (n1, n2) = (tv_1.count(), tv_2.count());
+    let n = n1 * n2 / (n1 + n2 + EVIDENCE_K);
+    n / (n + EVIDENCE_K)
+}
+
+/// PLN deduction truth value; node strengths are term probabilities
+pub(crate) fn deduction_tv(
+    tv_ab: TruthValue,
+    tv_bc: TruthValue,
+    s_a: f64,
+    s_b: f64,
+    s_c: f64,
+) -> TruthValue {
     TruthValue::new(
-        tv_ab.strength * tv_bc.strength,
-        tv_ab.confidence.min(tv_bc.confidence) * 0.9,
+        deduction_strength(tv_ab.strength, tv_bc.strength, s_a, s_b, s_c),
+        chained_confidence(tv_ab, tv_bc),
     )
 }
 
-/// Run PLN forward chaining up to `max_depth` iterations
+/// Property inheritance, A→B, P(B, X) ⊢ P(A, X): the chain term of
+/// deduction, since the property has no term probability of its own
+pub(crate) fn property_tv(tv_ab: TruthValue, tv_p: TruthValue) -> TruthValue {
+    TruthValue::new(
+        tv_ab.strength * tv_p.strength,
+        chained_confidence(tv_ab, tv_p),
+    )
+}
+
+/// `num / den`, or 0 when the denominator vanishes
+fn ratio(num: f64, den: f64) -> f64 {
+    if den.abs() < 1e-9 {
+        0.0
+    } else {
+        num / den
+    }
+}
+
+/// Bayes inversion strength, A→B ⊢ B→A, with node probabilities s_A, s_B:
+///   s_ba = s_ab · s_a / s_b
+pub(crate) fn inversion_strength(s_ab: f64, s_a: f64, s_b: f64) -> f64 {
+    ratio(s_ab * s_a, s_b).clamp(0.0, 1.0)
+}
+
+/// PLN induction strength, B→A, B→C ⊢ A→C, with node probabilities s_A, s_B, s_C.
+/// Bayes inverts B→A into A→B (s_ab = s_ba · s_b / s_a), which then chains
+/// through B by deduction.
+pub(crate) fn induction_strength(s_ba: f64, s_bc: f64, s_a: f64, s_b: f64, s_c: f64) -> f64 {
+    let s_ab = inversion_strength(s_ba, s_b, s_a);
+    deduction_strength(s_ab, s_bc, s_a, s_b, s_c)
+}
+
+/// PLN abduction strength, A→B, C→B ⊢ A→C, with node probabilities s_B, s_C:
+///   s_ac = s_ab · s_cb · s_c / s_b + (1 − s_ab) · (1 − s_cb) · s_c / (1 − s_b)
+pub(crate) fn abduction_strength(s_ab: f64, s_cb: f64, s_b: f64, s_c: f64) -> f64 {
+    let via_b = ratio(s_ab * s_cb * s_c, s_b);
+    let off_b = ratio((1.0 - s_ab) * (1.0 - s_cb) * s_c, 1.0 - s_b);
+    (via_b + off_b).clamp(0.0, 1.0)
+}
+
+/// Similarity from the two inheritance directions, A→B, B→A ⊢ A↔B:
+///   s_sim = 1 / (1/s_ab + 1/s_ba − 1)
+/// i.e. |A ∩ B| / |A ∪ B| when s_ab = |A ∩ B| / |A| and s_ba = |A ∩ B| / |B|.
+pub(crate) fn similarity_strength(s_ab: f64, s_ba: f64) -> f64 {
+    if s_ab < 1e-9 || s_ba < 1e-9 {
+        return 0.0;
+    }
+    (1.0 / (1.0 / s_ab + 1.0 / s_ba - 1.0)).clamp(0.0, 1.0)
+}
+
+/// Inheritance from similarity, A↔B ⊢ A→B, with node probabilities s_A, s_B:
+///   s_ab = (1 + s_b/s_a) · s_sim / (1 + s_sim)
+pub(crate) fn similarity_to_inheritance(s_sim: f64, s_a: f64, s_b: f64) -> f64 {
+    ((1.0 + ratio(s_b, s_a)) * s_sim / (1.0 + s_sim)).clamp(0.0, 1.0)
+}
+
+/// Run PLN forward chaining (deduction only) up to `max_depth` iterations
 pub fn forward_chain(space: &mut AtomSpace, max_depth: u32) -> Vec<Inference> {
+    forward_chain_with(space, max_depth, &[&Deduction])
+}
+
+/// Run PLN forward chaining with the given rules up to `max_depth` iterations.
+/// When several rules propose the same conclusion in one step, the rule
+/// listed first wins.
+pub fn forward_chain_with(
+    space: &mut AtomSpace,
+    max_depth: u32,
+    rules: &[&dyn InferenceRule],
+) -> Vec<Inference> {
+    semi_naive(space, max_depth, rules, 0)
+}
+
+/// Forward chaining over links added since the previous pass only. Every
+/// pair of older links was already combined, so each inference needs a
+/// premise at or past the rule's `AtomSpace::inference_watermark`; run to a
+/// fixpoint, the closure is the same as `forward_chain_with`, at a cost
+/// proportional to the new links.
+pub fn forward_chain_incremental(
+    space: &mut AtomSpace,
+    max_depth: u32,
+    rules: &[&dyn InferenceRule],
+) -> Vec<Inference> {
+    let from = rules
+        .iter()
+        .map(|r| space.inference_watermark(r.name()))
+        .min()
+        .unwrap_or(0);
+    semi_naive(space, max_depth, rules, from)
+}
+
+/// Seed the first step with links from id `from` on and each later step
+/// with the previous step's conclusions, then move the watermark past
+/// every link that has served as a seed
+fn semi_naive(
+    space: &mut AtomSpace,
+    max_depth: u32,
+    rules: &[&dyn InferenceRule],
+    from: AtomId,
+) -> Vec<Inference> {
+    let mut seeds: Vec<AtomId> = (from..space.next_id())
+        .filter(|&id| space.get(id).is_some_and(|a| !a.atom_type.is_node()))
+        .collect();
+    let mut frontier = from;
     let mut all = Vec::new();
     for _ in 0..max_depth {
-        let step = deduction_step(space);
-        if step.is_empty() {
+        if seeds.is_empty() {
             break;
         }
-        all.extend(step);
+        let next = space.next_id();
+        let step = chain_step(space, rules, &seeds, &|_, _| true, None, usize::MAX);
+        frontier = next;
+        seeds = step
+            .inferences
+            .iter()
+            .map(|inf| inf.conclusion_id)
+            .collect();
+        all.extend(step.inferences);
+    }
+    // Conclusions of the last step were never seeds; the next pass starts there
+    if seeds.is_empty() {
+        frontier = space.next_id();
+    }
+    for rule in rules {
+        space.set_inference_watermark(rule.name(), frontier);
     }
     all
 }
 
-/// One step of deduction: A->B, B->C |- A->C
-fn deduction_step(space: &mut AtomSpace) -> Vec<Inference> {
-    let inh_ids = space.get_by_type(AtomType::InheritanceLink);
-
-    // Collect all inheritance triples: (src, tgt, link_id, tv)
-    let links: Vec<(AtomId, AtomId, AtomId, TruthValue)> = inh_ids
+/// Forward chaining with the enabled rules of `control`, guided by the
+/// attentional `focus`: a premise is attended when it or one of its members
+/// is in focus. Every inference needs an attended premise or one added
+/// since the rule's `AtomSpace::inference_watermark`, matches in focus go
+/// first, then higher-STI ones, and at most `control.budget` conclusions
+/// are added. Conclusions seed the next step, so later steps can extend
+/// chains the user started. The watermarks move past every new link whose
+/// matches were all handled; matches cut by the budget hold them back.
+pub fn forward_chain_focused(
+    space: &mut AtomSpace,
+    max_depth: u32,
+    control: &InferenceControl,
+    focus: &[AtomId],
+) -> Vec<Inference> {
+    let rules = control.rules.enabled();
+    let marks: Vec<AtomId> = rules
         .iter()
-        .filter_map(|&id| {
-            let atom = space.get(id)?;
-            if atom.outgoing.len() == 2 {
-                Some((atom.outgoing[0], atom.outgoing[1], id, atom.tv))
-            } else {
-                None
-            }
-        })
+        .map(|r| space.inference_watermark(r.name()))
+        .collect();
+    let from = marks.iter().copied().min().unwrap_or(0);
+    // Links in focus, and links about an atom in focus
+    let attended: HashSet<AtomId> = focus
+        .iter()
+        .flat_map(|&id| std::iter::once(id).chain(space.get_incoming(id)))
+        .filter(|&id| space.get(id).is_some_and(|a| !a.atom_type.is_node()))
         .collect();
+    let mut seeds: Vec<AtomId> = (from..space.next_id())
+        .filter(|&id| space.get(id).is_some_and(|a| !a.atom_type.is_node()))
+        .chain(attended.iter().copied())
+        .collect();
+    seeds.sort_unstable();
+    seeds.dedup();
 
-    // Find deduction opportunities
-    let mut candidates: Vec<(AtomId, AtomId, TruthValue, AtomId, AtomId)> = Vec::new();
-    for &(a, b, ab_id, tv_ab) in &links {
-        for &(b2, c, bc_id, tv_bc) in &links {
-            if b != b2 || a == c {
-                continue;
-            }
-            // Skip if conclusion already exists
-            if space
-                .find_link(AtomType::InheritanceLink, &[a, c])
-                .is_some()
-            {
-                continue;
+    let eligible = |rule: usize, premises: &[AtomId]| {
+        premises
+            .iter()
+            .any(|p| attended.contains(p) || *p >= marks[rule])
+    };
+    let mut frontier = from;
+    let mut held = marks.iter().map(|_| AtomId::MAX).collect::<Vec<_>>();
+    let mut all = Vec::new();
+    for _ in 0..max_depth {
+        let budget = control.budget.saturating_sub(all.len());
+        if budget == 0 || seeds.is_empty() {
+            break;
+        }
+        let next = space.next_id();
+        let step = chain_step(space, &rules, &seeds, &eligible, Some(&attended), budget);
+        frontier = next;
+        // A cut match waits for its newest premise to be seeded again
+        for (rule, newest) in step.deferred {
+            if newest >= marks[rule] {
+                held[rule] = held[rule].min(newest);
             }
-            // Skip duplicates in this batch
-            if candidates
-                .iter()
-                .any(|(na, nc, _, _, _)| *na == a && *nc == c)
-            {
-                continue;
+        }
+        seeds = step
+            .inferences
+            .iter()
+            .map(|inf| inf.conclusion_id)
+            .collect();
+        all.extend(step.inferences);
+    }
+    if seeds.is_empty() {
+        frontier = space.next_id();
+    }
+    for (i, rule) in rules.iter().enumerate() {
+        let mark = frontier.min(held[i]).max(marks[i]);
+        space.set_inference_watermark(rule.name(), mark);
+    }
+    all
+}
+
+/// What one chaining step added, and the matches the budget cut as
+/// (rule index, newest premise)
+struct Step {
+    inferences: Vec<Inference>,
+    deferred: Vec<(usize, AtomId)>,
+}
+
+/// One step: every `eligible` match of a rule's premises that uses one of
+/// `seeds`, joined from the seed through the incoming index. With a `focus`,
+/// matches using it go first, then by premise STI; at most `budget`
+/// conclusions are added.
+fn chain_step(
+    space: &mut AtomSpace,
+    rules: &[&dyn InferenceRule],
+    seeds: &[AtomId],
+    eligible: &dyn Fn(usize, &[AtomId]) -> bool,
+    focus: Option<&HashSet<AtomId>>,
+    budget: usize,
+) -> Step {
+    // Find opportunities: (rule index, ground conclusion, tv, premise ids)
+    let mut candidates: Vec<(usize, Term, TruthValue, Vec<AtomId>)> = Vec::new();
+    let mut proposed: HashSet<Term> = HashSet::new();
+    for (index, &rule) in rules.iter().enumerate() {
+        let premises = rule.premises();
+        let conclusion = rule.conclusion();
+        for &seed in seeds {
+            for clause in 0..premises.clauses.len() {
+                for m in pattern::find_matches_with(space, &premises, clause, seed) {
+                    if !eligible(index, &m.clause_atoms) || !rule.admits(space, &m) {
+                        continue;
+                    }
+                    // Nothing already in the space or this batch
+                    let target = conclusion.substitute(&m.bindings);
+                    if proposed.contains(&target)
+                        || pattern::lookup(space, &target, &Bindings::new()).is_some()
+                    {
+                        continue;
+                    }
+                    let tv = rule.truth_value(space, &m);
+                    proposed.insert(target.clone());
+                    candidates.push((index, target, tv, m.clause_atoms));
+                }
             }
-            let tv = deduction_tv(tv_ab, tv_bc);
-            candidates.push((a, c, tv, ab_id, bc_id));
         }
     }
 
-    // Materialize new links
+    if let Some(focus) = focus {
+        let attended = |p: &[AtomId]| p.iter().any(|id| focus.contains(id));
+        let sti = |id: &AtomId| space.get(*id).map_or(0.0, |a| a.av.sti);
+        let priority = |p: &[AtomId]| p.iter().map(sti).sum::<f64>();
+        candidates.sort_by(|x, y| {
+            attended(&y.3).cmp(&attended(&x.3)).then(
+                priority(&y.3)
+                    .partial_cmp(&priority(&x.3))
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+    }
+    let deferred = candidates
+        .split_off(budget.min(candidates.len()))
+        .into_iter()
+        .map(|(rule, _, _, premises)| (rule, premises.into_iter().max().unwrap_or(0)))
+        .collect();
+
+    // Materialize new atoms
     let mut inferences = Vec::new();
-    for (a, c, tv, ab_id, bc_id) in candidates {
-        let (id, is_new) = space.add_link(AtomType::InheritanceLink, vec![a, c], tv);
-        if is_new {
-            inferences.push(Inference {
-                rule: "deduction".to_string(),
-                premises: vec![ab_id, bc_id],
-                conclusion_id: id,
+    for (index, target, tv, premises) in candidates {
+        let Some((id, true)) = pattern::instantiate(space, &target, &Bindings::new(), tv) else {
+            continue;
+        };
+        let rule = rules[index].name().to_string();
+        space.set_provenance(
+            id,
+            Provenance {
+                rule: rule.clone(),
+                premises: premises.clone(),
                 tv,
-            });
-        }
+            },
+        );
+        inferences.push(Inference {
+            rule,
+            premises,
+            conclusion_id: id,
+            tv,
+        });
     }
 
-    inferences
+    Step {
+        inferences,
+        deferred,
+    }
+}
+
+/// Where the rules disagree with atom `id`: its own premises imply
+/// otherwise, or it is a premise for the opposite of a stored atom
+pub fn contradictions(
+    space: &AtomSpace,
+    id: AtomId,
+    rules: &[&dyn InferenceRule],
+) -> Vec<Contradiction> {
+    let mut out = Vec::new();
+    let Some(tv) = space.get(id).map(|a| a.tv) else {
+        return out;
+    };
+    for &rule in rules {
+        let premises = rule.premises();
+        let conclusion = rule.conclusion();
+        // `id` as the conclusion
+        if let Some(bindings) = pattern::unify_atom(space, &conclusion, id) {
+            for m in pattern::find_matches_from(space, &premises, &bindings) {
+                if !rule.admits(space, &m) || m.clause_atoms.contains(&id) {
+                    continue;
+                }
+                let inferred = rule.truth_value(space, &m);
+                if contradicts(tv, inferred) {
+                    out.push(Contradiction {
+                        atom: id,
+                        stored: tv,
+                        rule: rule.name().to_string(),
+                        premises: m.clause_atoms,
+                        inferred,
+                    });
+                }
+            }
+        }
+        // `id` as a premise
+        for clause in 0..premises.clauses.len() {
+            for m in pattern::find_matches_with(space, &premises, clause, id) {
+                if !rule.admits(space, &m) {
+                    continue;
+                }
+                let target = conclusion.substitute(&m.bindings);
+                let Some(stored) = pattern::lookup(space, &target, &Bindings::new())
+                    .filter(|&t| t != id)
+                    .and_then(|t| space.get(t))
+                else {
+                    continue;
+                };
+                let inferred = rule.truth_value(space, &m);
+                if contradicts(stored.tv, inferred) {
+                    out.push(Contradiction {
+                        atom: stored.id,
+                        stored: stored.tv,
+                        rule: rule.name().to_string(),
+                        premises: m.clause_atoms,
+                        inferred,
+                    });
+                }
+            }
+        }
+    }
+    out
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::atomspace::AtomSpace;
+    use crate::rules::{
+        Abduction, Induction, Inversion, ParentSimilarity, PropertyInheritance,
+        SimilarityToInheritance,
+    };
+
+    const ALL: [&dyn InferenceRule; 3] = [&Deduction, &Induction, &Abduction];
 
     fn tv(s: f64, c: f64) -> TruthValue {
         TruthValue::new(s, c)
@@ -132,12 +507,66 @@ mod tests {
         let mut s = chain_abc();
         let inf = forward_chain(&mut s, 1);
         let t = inf[0].tv;
-        // s = 0.95 * 0.95 = 0.9025
-        assert!((t.strength - 0.9025).abs() < 0.001);
-        // c = min(0.90, 0.90) * 0.9 = 0.81
+        // s = 0.95·0.95 + 0.05·(0.95 − 0.95·0.95)/0.05 = 0.9025 + 0.0475
+        assert!((t.strength - 0.95).abs() < 0.001);
+        // n = 9·9/(9 + 9 + 1) = 81/19, c = n/(n + 1) = 81/100
         assert!((t.confidence - 0.81).abs() < 0.001);
     }
 
+    #[test]
+    fn deduction_falls_back_when_inconsistent() {
+        // P(B|A) = 0.9 is impossible with P(A) = 0.9, P(B) = 0.1
+        assert_eq!(deduction_strength(0.9, 0.8, 0.9, 0.1, 0.5), 0.9 * 0.8);
+        // B covers (almost) everything: A→C is just P(C)
+        assert_eq!(deduction_strength(0.995, 0.972, 0.5, 0.995, 0.97), 0.97);
+    }
+
+    #[test]
+    fn long_chains_keep_strength() {
+        let mut s = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut s);
+        forward_chain(&mut s, 3);
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let thing = s.find_node(AtomType::ConceptNode, "thing").unwrap();
+        let link = s
+            .find_link(AtomType::InheritanceLink, &[cat, thing])
+            .unwrap();
+        let tv = s.get(link).unwrap().tv;
+        // The simplified product gave 0.95·0.95·0.99·0.99 ≈ 0.88
+        assert!(tv.strength > 0.95, "cat→thing strength {}", tv.strength);
+        assert!(tv.confidence < 0.81);
+    }
+
+    #[test]
+    fn contradictions_found_from_either_side() {
+        let mut s = chain_abc();
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let mammal = s.find_node(AtomType::ConceptNode, "mammal").unwrap();
+        let animal = s.find_node(AtomType::ConceptNode, "animal").unwrap();
+        let (ca, _) = s.add_link(AtomType::InheritanceLink, vec![cat, animal], tv(0.05, 0.9));
+        let cm = s
+            .find_link(AtomType::InheritanceLink, &[cat, mammal])
+            .unwrap();
+
+        // cat→animal is the conclusion its premises contradict
+        let found = contradictions(&s, ca, &[&Deduction]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].atom, ca);
+        assert_eq!(found[0].premises[0], cm);
+        assert!(found[0].inferred.strength > 0.9);
+        // cat→mammal is a premise for the opposite of cat→animal
+        let found = contradictions(&s, cm, &[&Deduction]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].atom, ca);
+    }
+
+    #[test]
+    fn unsure_evidence_does_not_contradict() {
+        assert!(contradicts(tv(0.05, 0.9), tv(0.95, 0.8)));
+        assert!(!contradicts(tv(0.05, 0.9), tv(0.95, 0.3)));
+        assert!(!contradicts(tv(0.6, 0.9), tv(0.95, 0.9)));
+    }
+
     #[test]
     fn no_self_loops() {
         let mut s = AtomSpace::new();
@@ -200,6 +629,432 @@ mod tests {
         }
     }
 
+    /// cat→mammal, cat→pet and dog→mammal, with node probabilities
+    fn shared_terms() -> AtomSpace {
+        let mut s = AtomSpace::new();
+        let (cat, _) = s.add_node(AtomType::ConceptNode, "cat", tv(0.3, 0.9));
+        let (dog, _) = s.add_node(AtomType::ConceptNode, "dog", tv(0.4, 0.9));
+        let (mammal, _) = s.add_node(AtomType::ConceptNode, "mammal", tv(0.6, 0.9));
+        let (pet, _) = s.add_node(AtomType::ConceptNode, "pet", tv(0.5, 0.9));
+        s.add_link(AtomType::InheritanceLink, vec![cat, mammal], tv(0.9, 0.9));
+        s.add_link(AtomType::InheritanceLink, vec![cat, pet], tv(0.8, 0.8));
+        s.add_link(AtomType::InheritanceLink, vec![dog, mammal], tv(0.9, 0.9));
+        s
+    }
+
+    fn inferred<'a>(s: &AtomSpace, inf: &'a [Inference], a: &str, c: &str) -> &'a Inference {
+        let a = s.find_node(AtomType::ConceptNode, a).unwrap();
+        let c = s.find_node(AtomType::ConceptNode, c).unwrap();
+        inf.iter()
+            .find(|i| s.get(i.conclusion_id).unwrap().outgoing == vec![a, c])
+            .expect("conclusion inferred")
+    }
+
+    #[test]
+    fn deduction_only_by_default() {
+        let mut s = shared_terms();
+        assert!(forward_chain(&mut s, 2).is_empty());
+    }
+
+    #[test]
+    fn induction_from_shared_instance() {
+        let mut s = shared_terms();
+        let inf = forward_chain_with(&mut s, 1, &[&Induction]);
+        let i = inferred(&s, &inf, "mammal", "pet");
+        assert_eq!(i.rule, "induction");
+        // s_ab = 0.9·0.3/0.6 = 0.45; off-B = (0.5 − 0.3·0.8)/0.7 ≈ 0.3714
+        // s = 0.45·0.8 + 0.55·0.3714 ≈ 0.5643
+        assert!((i.tv.strength - 0.5643).abs() < 0.001);
+        // c = min(0.9, 0.8) · 0.8
+        assert!((i.tv.confidence - 0.64).abs() < 0.001);
+        assert!(inf.iter().all(|i| i.rule == "induction"));
+    }
+
+    #[test]
+    fn abduction_from_shared_parent() {
+        let mut s = shared_terms();
+        let inf = forward_chain_with(&mut s, 1, &[&Abduction]);
+        let i = inferred(&s, &inf, "cat", "dog");
+        assert_eq!(i.rule, "abduction");
+        // s = 0.9·0.9·0.4/0.6 + 0.1·0.1·0.4/0.4 = 0.54 + 0.01
+        assert!((i.tv.strength - 0.55).abs() < 0.001);
+        assert!((i.tv.confidence - 0.72).abs() < 0.001);
+        // Symmetric premises also yield dog→cat
+        inferred(&s, &inf, "dog", "cat");
+    }
+
+    #[test]
+    fn inversion_by_bayes_rule() {
+        let mut s = shared_terms();
+        let inf = forward_chain_with(&mut s, 1, &[&Inversion]);
+        let i = inferred(&s, &inf, "mammal", "cat");
+        assert_eq!(i.rule, "inversion");
+        // Taxonomy: P(cat) = 1/4, P(mammal) = 3/4; s = 0.9·(1/4)/(3/4)
+        assert!((i.tv.strength - 0.3).abs() < 1e-9);
+        assert!((i.tv.confidence - 0.54).abs() < 1e-9);
+        assert_eq!(inf.len(), 3, "one per asserted link");
+    }
+
+    #[test]
+    fn inversion_leaves_conclusions_and_inconsistent_links() {
+        let mut s = shared_terms();
+        let inf = forward_chain_with(&mut s, 3, &[&Deduction, &Inversion]);
+        assert_eq!(inferred(&s, &inf, "dog", "cat").rule, "deduction");
+        // dog→cat is a conclusion, so cat→dog comes from deduction only
+        assert_eq!(inferred(&s, &inf, "cat", "dog").rule, "deduction");
+        for i in inf.iter().filter(|i| i.rule == "inversion") {
+            assert!(s.provenance(i.premises[0]).is_none());
+        }
+
+        // Every concept is a b, so P(b|a) = 0.9 is impossible
+        let mut s = AtomSpace::new();
+        let (a, _) = s.add_node(AtomType::ConceptNode, "a", tv(0.9, 0.9));
+        let (b, _) = s.add_node(AtomType::ConceptNode, "b", tv(0.9, 0.9));
+        s.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.9, 0.9));
+        assert!(forward_chain_with(&mut s, 1, &[&Inversion]).is_empty());
+    }
+
+    #[test]
+    fn similarity_from_shared_parent() {
+        let mut s = shared_terms();
+        let inf = forward_chain_with(&mut s, 1, &[&ParentSimilarity]);
+        assert_eq!(inf.len(), 1, "cat↔dog and dog↔cat are one conclusion");
+        let sim = s.get(inf[0].conclusion_id).unwrap();
+        assert_eq!(sim.atom_type, AtomType::SimilarityLink);
+        // Term probabilities from the taxonomy: cat, dog 1/4, mammal 3/4.
+        // Both directions abduce 0.27 + 0.01; 1/(2/0.28 − 1)
+        assert!((inf[0].tv.strength - 0.1628).abs() < 0.001);
+        assert!((inf[0].tv.confidence - 0.72).abs() < 0.001);
+        assert!(forward_chain_with(&mut s, 1, &[&ParentSimilarity]).is_empty());
+    }
+
+    #[test]
+    fn broad_parents_do_not_pass_properties_across() {
+        let mut s = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut s);
+        let rules: [&dyn InferenceRule; 4] = [
+            &Deduction,
+            &PropertyInheritance,
+            &ParentSimilarity,
+            &SimilarityToInheritance,
+        ];
+        forward_chain_with(&mut s, 4, &rules);
+        let has = s
+            .find_node(AtomType::PredicateNode, "has-property")
+            .unwrap();
+        let holds = |a: &str, p: &str| {
+            let a = s.find_node(AtomType::ConceptNode, a).unwrap();
+            let p = s.find_node(AtomType::ConceptNode, p).unwrap();
+            s.find_link(AtomType::ListLink, &[a, p])
+                .and_then(|l| s.find_link(AtomType::EvaluationLink, &[has, l]))
+                .map_or(0.0, |e| s.get(e).unwrap().tv.strength)
+        };
+        assert!(holds("cat", "can-fly") < 0.5);
+        assert!(holds("plant", "warm-blooded") < 0.5);
+        assert!(holds("cat", "warm-blooded") > 0.5);
+    }
+
+    #[test]
+    fn similarity_converts_to_both_inheritance_directions() {
+        let mut s = shared_terms();
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let dog = s.find_node(AtomType::ConceptNode, "dog").unwrap();
+        s.add_link(AtomType::SimilarityLink, vec![dog, cat], tv(0.5, 0.8));
+        let inf = forward_chain_with(&mut s, 1, &[&SimilarityToInheritance]);
+        assert_eq!(inf.len(), 2);
+        // (1 + 0.4/0.3) · 0.5/1.5 and (1 + 0.3/0.4) · 0.5/1.5
+        let cd = inferred(&s, &inf, "cat", "dog");
+        assert!((cd.tv.strength - 0.7778).abs() < 0.001);
+        let dc = inferred(&s, &inf, "dog", "cat");
+        assert!((dc.tv.strength - 0.5833).abs() < 0.001);
+
+        // And back: both directions give the similarity they came from
+        assert!((similarity_strength(0.5, 0.5) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(similarity_strength(0.0, 0.9), 0.0);
+    }
+
+    #[test]
+    fn members_inherit_class_properties() {
+        let mut s = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut s);
+        crate::parse::parse_input(&mut s, "mammal likes milk");
+        let inf = forward_chain_with(&mut s, 1, &[&PropertyInheritance]);
+        let facts: Vec<String> = inf
+            .iter()
+            .map(|i| {
+                let e = s.get(i.conclusion_id).unwrap();
+                let list = &s.get(e.outgoing[1]).unwrap().outgoing;
+                format!(
+                    "{} {} {}",
+                    s.short_name(list[0]),
+                    s.short_name(e.outgoing[0]),
+                    s.short_name(list[1])
+                )
+            })
+            .collect();
+        assert!(facts.contains(&"cat has-property warm-blooded".to_string()));
+        assert!(facts.contains(&"eagle has-property can-fly".to_string()));
+        assert!(facts.contains(&"dog likes milk".to_string()));
+        assert!(!facts
+            .iter()
+            .any(|f| f.starts_with("salmon has-property warm")));
+
+        let at = facts
+            .iter()
+            .position(|f| f == "cat has-property warm-blooded")
+            .unwrap();
+        let cat_warm = inf[at].tv;
+        // 0.95 · 0.95, n = 9·9/19 → c = 0.81
+        assert!((cat_warm.strength - 0.9025).abs() < 1e-9);
+        assert!((cat_warm.confidence - 0.81).abs() < 1e-9);
+    }
+
+    #[test]
+    fn strengths_stay_probabilities_at_extremes() {
+        assert_eq!(abduction_strength(0.9, 0.9, 1.0, 0.9), 0.9 * 0.9 * 0.9);
+        assert!(abduction_strength(0.1, 0.1, 0.01, 1.0) <= 1.0);
+        assert!(induction_strength(0.9, 0.9, 0.0, 0.5, 0.5) >= 0.0);
+    }
+
+    #[test]
+    fn focused_chaining_ignores_unattended_links() {
+        let mut s = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut s);
+        let eagle = s.find_node(AtomType::ConceptNode, "eagle").unwrap();
+        let bird = s.find_node(AtomType::ConceptNode, "bird").unwrap();
+        let eb = s
+            .find_link(AtomType::InheritanceLink, &[eagle, bird])
+            .unwrap();
+        s.set_sti(eb, 10.0);
+        // The ontology is old news: only the focus can reach into it
+        let control = InferenceControl::default();
+        for rule in control.rules.enabled() {
+            s.set_inference_watermark(rule.name(), s.next_id());
+        }
+        let inf = forward_chain_focused(&mut s, 3, &control, &[eb]);
+        assert!(!inf.is_empty());
+        // Every conclusion is about eagles; cat, cucumber etc. stay untouched
+        for i in &inf {
+            let c = s.get(i.conclusion_id).unwrap();
+            let subject = match c.atom_type {
+                AtomType::EvaluationLink => s.get(c.outgoing[1]).unwrap().outgoing[0],
+                _ => c.outgoing[0],
+            };
+            assert_eq!(subject, eagle);
+        }
+        let thing = s.find_node(AtomType::ConceptNode, "thing").unwrap();
+        assert!(s
+            .find_link(AtomType::InheritanceLink, &[eagle, thing])
+            .is_some());
+    }
+
+    #[test]
+    fn focus_reaches_links_through_their_members() {
+        let mut s = chain_abc();
+        let control = InferenceControl::default();
+        for rule in control.rules.enabled() {
+            s.set_inference_watermark(rule.name(), s.next_id());
+        }
+        // Nothing in focus and nothing new: nothing inferred
+        assert!(forward_chain_focused(&mut s, 2, &control, &[]).is_empty());
+        // Attending to a node attends to the links about it
+        let cat = s.find_node(AtomType::ConceptNode, "cat").unwrap();
+        let inf = forward_chain_focused(&mut s, 2, &control, &[cat]);
+        assert_eq!(inf.len(), 1, "cat\u{2192}animal");
+    }
+
+    #[test]
+    fn focused_chaining_advances_watermarks() {
+        let mut s = chain_abc();
+        let control = InferenceControl::default();
+        // Unattended but new: a→c is still concluded, once
+        assert_eq!(forward_chain_focused(&mut s, 2, &control, &[]).len(), 1);
+        assert_eq!(s.inference_watermark("deduction"), s.next_id());
+        assert!(forward_chain_focused(&mut s, 2, &control, &[]).is_empty());
+
+        add_isa(&mut s, "kitten", "cat");
+        let new_link = s.next_id() - 1;
+        let inf = forward_chain_focused(&mut s, 1, &control, &[]);
+        assert!(!inf.is_empty());
+        assert!(inf.iter().all(|i| i.premises.contains(&new_link)));
+    }
+
+    #[test]
+    fn budget_cut_matches_hold_the_watermark() {
+        let mut s = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut s);
+        let control = InferenceControl {
+            budget: 5,
+            ..Default::default()
+        };
+        let mut full = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut full);
+        forward_chain_with(&mut full, 100, &control.rules.enabled());
+        // Each run adds five; what the budget cut is picked up by later runs
+        let first = forward_chain_focused(&mut s, 1, &control, &[]);
+        assert_eq!(first.len(), 5);
+        assert!(s.inference_watermark("deduction") < first[0].conclusion_id);
+        while !forward_chain_focused(&mut s, 1, &control, &[]).is_empty() {}
+        assert_eq!(inheritance_pairs(&s), inheritance_pairs(&full));
+    }
+
+    #[test]
+    fn budget_caps_inferences_per_run() {
+        let mut s = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut s);
+        let focus = s.get_by_type(AtomType::InheritanceLink);
+        let control = InferenceControl {
+            budget: 5,
+            ..Default::default()
+        };
+        let inf = forward_chain_focused(&mut s, 3, &control, &focus);
+        assert_eq!(inf.len(), 5);
+    }
+
+    #[test]
+    fn index_join_matches_pair_scan() {
+        let mut base = AtomSpace::new();
+        crate::ontology::load_base_ontology(&mut base);
+        let links: Vec<(AtomId, AtomId)> = base
+            .get_by_type(AtomType::InheritanceLink)
+            .into_iter()
+            .map(|id| {
+                let o = &base.get(id).unwrap().outgoing;
+                (o[0], o[1])
+            })
+            .collect();
+        for rule in ALL {
+            // Reference: every ordered pair of distinct links
+            let mut expected = HashSet::new();
+            for (i, &(x1, y1)) in links.iter().enumerate() {
+                for (j, &(x2, y2)) in links.iter().enumerate() {
+                    let (shared, ac) = match rule.name() {
+                        "deduction" => (y1 == x2, (x1, y2)),
+                        "induction" => (x1 == x2, (y1, y2)),
+                        _ => (y1 == y2, (x1, x2)),
+                    };
+                    if i != j && shared && ac.0 != ac.1 && !links.contains(&ac) {
+                        expected.insert(ac);
+                    }
+                }
+            }
+            let mut s = AtomSpace::from_parts(
+                base.all_atoms_sorted().into_iter().cloned().collect(),
+                base.next_id(),
+                0,
+            );
+            let got: HashSet<_> = forward_chain_with(&mut s, 1, &[rule])
+                .iter()
+                .map(|i| {
+                    let o = &s.get(i.conclusion_id).unwrap().outgoing;
+                    (o[0], o[1])
+                })
+                .collect();
+            assert_eq!(got, expected, "{}", rule.name());
+        }
+    }
+
+    fn inheritance_pairs(s: &AtomSpace) -> HashSet<(String, String)> {
+        s.get_by_type(AtomType::InheritanceLink)
+            .into_iter()
+            .map(|id| {
+                let o = &s.get(id).unwrap().outgoing;
+                (s.short_name(o[0]), s.short_name(o[1]))
+            })
+            .collect()
+    }
+
+    fn add_isa(s: &mut AtomSpace, a: &str, b: &str) {
+        let (a, _) = s.add_node(AtomType::ConceptNode, a, tv(0.9, 0.9));
+        let (b, _) = s.add_node(AtomType::ConceptNode, b, tv(0.9, 0.9));
+        s.add_link(AtomType::InheritanceLink, vec![a, b], tv(0.95, 0.9));
+    }
+
+    #[test]
+    fn incremental_reaches_full_closure() {
+        let batches: [&[(&str, &str)]; 3] = [
+            &[("cat", "mammal"), ("mammal", "animal")],
+            &[("animal", "living-thing"), ("dog", "mammal")],
+            &[("living-thing", "thing"), ("kitten", "cat")],
+        ];
+        let mut inc = AtomSpace::new();
+        let mut full = AtomSpace::new();
+        for batch in batches {
+            for &(a, b) in batch {
+                add_isa(&mut inc, a, b);
+                add_isa(&mut full, a, b);
+            }
+            forward_chain_incremental(&mut inc, 10, &ALL);
+        }
+        forward_chain_with(&mut full, 10, &ALL);
+        assert_eq!(inheritance_pairs(&inc), inheritance_pairs(&full));
+    }
+
+    #[test]
+    fn incremental_pairs_only_new_links() {
+        let mut s = chain_abc();
+        assert_eq!(forward_chain_incremental(&mut s, 5, &[&Deduction]).len(), 1);
+        assert_eq!(s.inference_watermark("deduction"), s.next_id());
+        assert!(forward_chain_incremental(&mut s, 5, &[&Deduction]).is_empty());
+
+        add_isa(&mut s, "kitten", "cat");
+        let new_link = s.next_id() - 1;
+        let inf = forward_chain_incremental(&mut s, 1, &[&Deduction]);
+        // kitten→mammal and kitten→animal, both resting on the new link
+        assert_eq!(inf.len(), 2);
+        assert!(inf.iter().all(|i| i.premises.contains(&new_link)));
+    }
+
+    #[test]
+    fn watermark_resumes_unfinished_depth_and_tracks_rules() {
+        let mut s = AtomSpace::new();
+        for (a, b) in [("a", "b"), ("b", "c"), ("c", "d"), ("d", "e")] {
+            add_isa(&mut s, a, b);
+        }
+        // Depth 1 leaves a→c, b→d, c→e unpaired; the next pass continues
+        assert_eq!(forward_chain_incremental(&mut s, 1, &[&Deduction]).len(), 3);
+        assert!(s.inference_watermark("deduction") < s.next_id());
+        let more = forward_chain_incremental(&mut s, 10, &[&Deduction]);
+        assert_eq!(more.len(), 3, "a→d, b→e, a→e");
+
+        // Abduction has never run, so it still sees every link
+        assert_eq!(s.inference_watermark("abduction"), 0);
+        assert!(!forward_chain_incremental(&mut s, 1, &[&Abduction]).is_empty());
+    }
+
+    /// A third-party rule whose conclusion breaks NotLink's arity
+    struct IllTyped;
+
+    impl InferenceRule for IllTyped {
+        fn name(&self) -> &str {
+            "ill-typed"
+        }
+
+        fn premises(&self) -> pattern::Pattern {
+            pattern::Pattern::new(vec![Term::link(
+                AtomType::InheritanceLink,
+                vec![Term::var("A"), Term::var("B")],
+            )])
+        }
+
+        fn conclusion(&self) -> Term {
+            Term::link(AtomType::NotLink, vec![Term::var("A"), Term::var("B")])
+        }
+
+        fn truth_value(&self, _space: &AtomSpace, _m: &pattern::Match) -> TruthValue {
+            TruthValue::default_tv()
+        }
+    }
+
+    #[test]
+    fn ill_typed_conclusions_are_skipped() {
+        let mut s = chain_abc();
+        let inf = forward_chain_with(&mut s, 2, &[&IllTyped, &Deduction]);
+        assert_eq!(inf.len(), 1);
+        assert_eq!(inf[0].rule, "deduction");
+        assert!(s.get_by_type(AtomType::NotLink).is_empty());
+    }
+
     #[test]
     fn empty_space_no_inferences() {
         let mut s = AtomSpace::new();
@@ -0,0 +1,602 @@
+//! Inference rules — what PLN may conclude from which premises
+//! A rule declares a premise pattern, the conclusion built from its
+//! bindings and a truth-value formula. Crates outside coggy add rules by
+//! implementing `InferenceRule` and registering them in a `RuleRegistry`.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::atom::*;
+use crate::atomspace::AtomSpace;
+use crate::pattern::{Match, Pattern, Term};
+use crate::pln;
+
+/// Confidence kept from the weaker premise by induction and abduction;
+/// generalizing from one shared term is weaker evidence than a chain
+const GENERALIZATION_DISCOUNT: f64 = 0.8;
+
+/// Confidence kept by inversion, which rests on the term probabilities as
+/// much as on the link
+const INVERSION_DISCOUNT: f64 = 0.6;
+
+pub trait InferenceRule: Send + Sync {
+    /// Unique name, recorded in provenance and used to enable the rule
+    fn name(&self) -> &str;
+
+    /// Clauses the premises must match, joined on shared variables.
+    /// Premise ids are recorded in clause order.
+    fn premises(&self) -> Pattern;
+
+    /// The conclusion, in terms of the premise variables
+    fn conclusion(&self) -> Term;
+
+    /// Truth value of the conclusion for one match of the premises
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue;
+
+    /// Whether a match may fire. By default distinct variables must bind
+    /// distinct atoms, which rules out conclusions like A→A.
+    fn admits(&self, _space: &AtomSpace, m: &Match) -> bool {
+        let mut seen = HashSet::new();
+        m.bindings.values().all(|id| seen.insert(*id))
+    }
+}
+
+/// Truth value of the atom a clause matched
+pub fn premise_tv(space: &AtomSpace, m: &Match, clause: usize) -> TruthValue {
+    m.clause_atoms
+        .get(clause)
+        .and_then(|&id| space.get(id))
+        .map_or(TruthValue::default_tv(), |a| a.tv)
+}
+
+/// Probability of the term bound to `var`, from the taxonomy: the share of
+/// concepts that inherit from it, itself included. A node's strength says
+/// how sure the space is of the term, not how general it is, and would make
+/// every parent look as specific as its children.
+pub fn term_probability(space: &AtomSpace, m: &Match, var: &str) -> f64 {
+    let Some(&id) = m.bindings.get(var) else {
+        return 0.0;
+    };
+    let mut seen = HashSet::from([id]);
+    let mut stack = vec![id];
+    while let Some(cur) = stack.pop() {
+        for &l in space.incoming_at(cur, 1) {
+            let Some(link) = space.get(l) else { continue };
+            if link.atom_type == AtomType::InheritanceLink
+                && link.tv.strength >= 0.5
+                && seen.insert(link.outgoing[0])
+            {
+                stack.push(link.outgoing[0]);
+            }
+        }
+    }
+    let concepts = space.count_by_type(AtomType::ConceptNode).max(seen.len());
+    seen.len() as f64 / concepts as f64
+}
+
+/// Strength of the atom bound to `var`, read as its probability
+pub fn term_strength(space: &AtomSpace, m: &Match, var: &str) -> f64 {
+    m.bindings
+        .get(var)
+        .and_then(|&id| space.get(id))
+        .map_or(0.0, |a| a.tv.strength)
+}
+
+fn isa(a: &str, b: &str) -> Term {
+    Term::inheritance(Term::var(a), Term::var(b))
+}
+
+fn similar(a: &str, b: &str) -> Term {
+    Term::similarity(Term::var(a), Term::var(b))
+}
+
+/// A→B, B→C ⊢ A→C
+pub struct Deduction;
+
+impl InferenceRule for Deduction {
+    fn name(&self) -> &str {
+        "deduction"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("A", "B"), isa("B", "C")])
+    }
+
+    fn conclusion(&self) -> Term {
+        isa("A", "C")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        pln::deduction_tv(
+            premise_tv(space, m, 0),
+            premise_tv(space, m, 1),
+            term_strength(space, m, "A"),
+            term_strength(space, m, "B"),
+            term_strength(space, m, "C"),
+        )
+    }
+}
+
+/// B→A, B→C ⊢ A→C (generalize from a shared instance)
+pub struct Induction;
+
+impl InferenceRule for Induction {
+    fn name(&self) -> &str {
+        "induction"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("B", "A"), isa("B", "C")])
+    }
+
+    fn conclusion(&self) -> Term {
+        isa("A", "C")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        let (tv_ba, tv_bc) = (premise_tv(space, m, 0), premise_tv(space, m, 1));
+        TruthValue::new(
+            pln::induction_strength(
+                tv_ba.strength,
+                tv_bc.strength,
+                term_strength(space, m, "A"),
+                term_strength(space, m, "B"),
+                term_strength(space, m, "C"),
+            ),
+            tv_ba.confidence.min(tv_bc.confidence) * GENERALIZATION_DISCOUNT,
+        )
+    }
+}
+
+/// A→B, C→B ⊢ A→C (generalize from a shared parent)
+pub struct Abduction;
+
+impl InferenceRule for Abduction {
+    fn name(&self) -> &str {
+        "abduction"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("A", "B"), isa("C", "B")])
+    }
+
+    fn conclusion(&self) -> Term {
+        isa("A", "C")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        let (tv_ab, tv_cb) = (premise_tv(space, m, 0), premise_tv(space, m, 1));
+        TruthValue::new(
+            pln::abduction_strength(
+                tv_ab.strength,
+                tv_cb.strength,
+                term_strength(space, m, "B"),
+                term_strength(space, m, "C"),
+            ),
+            tv_ab.confidence.min(tv_cb.confidence) * GENERALIZATION_DISCOUNT,
+        )
+    }
+}
+
+/// A→B ⊢ B→A by Bayes' rule. Only asserted links are inverted, and only
+/// when their strength is consistent with the term probabilities: inverting
+/// conclusions would let deduction connect nearly every pair of terms. The
+/// probabilities come from the taxonomy (`term_probability`); concept
+/// strengths, all near 0.9, would make every inversion nearly as strong as
+/// the link itself.
+pub struct Inversion;
+
+impl InferenceRule for Inversion {
+    fn name(&self) -> &str {
+        "inversion"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("A", "B")])
+    }
+
+    fn conclusion(&self) -> Term {
+        isa("B", "A")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        let tv_ab = premise_tv(space, m, 0);
+        TruthValue::new(
+            pln::inversion_strength(
+                tv_ab.strength,
+                term_probability(space, m, "A"),
+                term_probability(space, m, "B"),
+            ),
+            tv_ab.confidence * INVERSION_DISCOUNT,
+        )
+    }
+
+    fn admits(&self, space: &AtomSpace, m: &Match) -> bool {
+        let Some(&ab) = m.clause_atoms.first() else {
+            return false;
+        };
+        m.bindings["A"] != m.bindings["B"]
+            && space.provenance(ab).is_none()
+            && pln::conditional_consistent(
+                term_probability(space, m, "A"),
+                term_probability(space, m, "B"),
+                premise_tv(space, m, 0).strength,
+            )
+    }
+}
+
+/// A→C, B→C ⊢ A↔B (alike through a shared parent). Each direction is an
+/// abduction through C; the two are combined into a similarity. Term
+/// probabilities come from the taxonomy (`term_probability`), so sharing
+/// a near-universal parent such as "thing" is weak evidence.
+pub struct ParentSimilarity;
+
+impl InferenceRule for ParentSimilarity {
+    fn name(&self) -> &str {
+        "parent-similarity"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("A", "C"), isa("B", "C")])
+    }
+
+    fn conclusion(&self) -> Term {
+        similar("A", "B")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        let (tv_ac, tv_bc) = (premise_tv(space, m, 0), premise_tv(space, m, 1));
+        let [s_a, s_b, s_c] = ["A", "B", "C"].map(|v| term_probability(space, m, v));
+        TruthValue::new(
+            pln::similarity_strength(
+                pln::abduction_strength(tv_ac.strength, tv_bc.strength, s_c, s_b),
+                pln::abduction_strength(tv_bc.strength, tv_ac.strength, s_c, s_a),
+            ),
+            tv_ac.confidence.min(tv_bc.confidence) * GENERALIZATION_DISCOUNT,
+        )
+    }
+}
+
+/// C→A, C→B ⊢ A↔B (alike through a shared child). Each direction is an
+/// induction through C; the two are combined into a similarity.
+pub struct ChildSimilarity;
+
+impl InferenceRule for ChildSimilarity {
+    fn name(&self) -> &str {
+        "child-similarity"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("C", "A"), isa("C", "B")])
+    }
+
+    fn conclusion(&self) -> Term {
+        similar("A", "B")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        let (tv_ca, tv_cb) = (premise_tv(space, m, 0), premise_tv(space, m, 1));
+        let [s_a, s_b, s_c] = ["A", "B", "C"].map(|v| term_strength(space, m, v));
+        TruthValue::new(
+            pln::similarity_strength(
+                pln::induction_strength(tv_ca.strength, tv_cb.strength, s_a, s_c, s_b),
+                pln::induction_strength(tv_cb.strength, tv_ca.strength, s_b, s_c, s_a),
+            ),
+            tv_ca.confidence.min(tv_cb.confidence) * GENERALIZATION_DISCOUNT,
+        )
+    }
+}
+
+/// A→B, B→A ⊢ A↔B
+pub struct InheritanceToSimilarity;
+
+impl InferenceRule for InheritanceToSimilarity {
+    fn name(&self) -> &str {
+        "inheritance-to-similarity"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("A", "B"), isa("B", "A")])
+    }
+
+    fn conclusion(&self) -> Term {
+        similar("A", "B")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        let (tv_ab, tv_ba) = (premise_tv(space, m, 0), premise_tv(space, m, 1));
+        TruthValue::new(
+            pln::similarity_strength(tv_ab.strength, tv_ba.strength),
+            tv_ab.confidence.min(tv_ba.confidence),
+        )
+    }
+}
+
+/// A↔B ⊢ A→B. The premise matches either way round, so each similarity
+/// yields both directions.
+pub struct SimilarityToInheritance;
+
+impl InferenceRule for SimilarityToInheritance {
+    fn name(&self) -> &str {
+        "similarity-to-inheritance"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![similar("A", "B")])
+    }
+
+    fn conclusion(&self) -> Term {
+        isa("A", "B")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        let tv = premise_tv(space, m, 0);
+        TruthValue::new(
+            pln::similarity_to_inheritance(
+                tv.strength,
+                term_strength(space, m, "A"),
+                term_strength(space, m, "B"),
+            ),
+            tv.confidence,
+        )
+    }
+}
+
+/// A→B, P(B, X) ⊢ P(A, X): members inherit what holds of their class,
+/// for any predicate P stated as EvaluationLink(P, ListLink(B, X))
+pub struct PropertyInheritance;
+
+fn evaluation(pred: &str, subject: &str, object: &str) -> Term {
+    Term::link(
+        AtomType::EvaluationLink,
+        vec![
+            Term::var(pred),
+            Term::link(
+                AtomType::ListLink,
+                vec![Term::var(subject), Term::var(object)],
+            ),
+        ],
+    )
+}
+
+impl InferenceRule for PropertyInheritance {
+    fn name(&self) -> &str {
+        "property-inheritance"
+    }
+
+    fn premises(&self) -> Pattern {
+        Pattern::new(vec![isa("A", "B"), evaluation("P", "B", "X")])
+            .with_type("P", &[AtomType::PredicateNode])
+    }
+
+    fn conclusion(&self) -> Term {
+        evaluation("P", "A", "X")
+    }
+
+    fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+        pln::property_tv(premise_tv(space, m, 0), premise_tv(space, m, 1))
+    }
+}
+
+/// The rules a deployment knows about, in priority order, each enabled or
+/// not. When two rules propose the same conclusion, the earlier one wins.
+#[derive(Clone, Default)]
+pub struct RuleRegistry {
+    rules: Vec<(Arc<dyn InferenceRule>, bool)>,
+}
+
+impl RuleRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deduction and property inheritance enabled; the other built-in
+    /// rules registered but disabled
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Deduction, true);
+        registry.register(PropertyInheritance, true);
+        registry.register(Induction, false);
+        registry.register(Abduction, false);
+        registry.register(Inversion, false);
+        registry.register(ParentSimilarity, false);
+        registry.register(ChildSimilarity, false);
+        registry.register(InheritanceToSimilarity, false);
+        registry.register(SimilarityToInheritance, false);
+        registry
+    }
+
+    /// Add a rule at the lowest priority, or replace the rule of the same
+    /// name in place
+    pub fn register(&mut self, rule: impl InferenceRule + 'static, enabled: bool) {
+        let rule: Arc<dyn InferenceRule> = Arc::new(rule);
+        match self.rules.iter_mut().find(|(r, _)| r.name() == rule.name()) {
+            Some(slot) => *slot = (rule, enabled),
+            None => self.rules.push((rule, enabled)),
+        }
+    }
+
+    /// Returns false if no rule has that name
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.rules.iter_mut().find(|(r, _)| r.name() == name) {
+            Some((_, on)) => {
+                *on = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enable exactly the named rules. On an unknown name, returns it and
+    /// leaves the registry unchanged.
+    pub fn enable_only(&mut self, names: &[&str]) -> Result<(), String> {
+        if let Some(unknown) = names.iter().find(|&&n| self.get(n).is_none()) {
+            return Err(unknown.to_string());
+        }
+        for (rule, on) in &mut self.rules {
+            *on = names.contains(&rule.name());
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn InferenceRule> {
+        self.rules
+            .iter()
+            .find(|(r, _)| r.name() == name)
+            .map(|(r, _)| r.as_ref())
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.rules.iter().any(|(r, on)| *on && r.name() == name)
+    }
+
+    /// Enabled rules in priority order
+    pub fn enabled(&self) -> Vec<&dyn InferenceRule> {
+        self.rules
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(r, _)| r.as_ref())
+            .collect()
+    }
+
+    /// Every registered rule in priority order
+    pub fn all(&self) -> Vec<&dyn InferenceRule> {
+        self.rules.iter().map(|(r, _)| r.as_ref()).collect()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.rules.iter().map(|(r, _)| r.name()).collect()
+    }
+}
+
+impl fmt::Debug for RuleRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.rules.iter().map(|(r, on)| (r.name(), on)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A domain rule over EvaluationLinks, as an external crate would write it:
+    /// part-of(A, B), part-of(B, C) ⊢ part-of(A, C)
+    struct PartOf;
+
+    fn part_of(a: &str, b: &str) -> Term {
+        Term::link(
+            AtomType::EvaluationLink,
+            vec![
+                Term::predicate("part-of"),
+                Term::link(AtomType::ListLink, vec![Term::var(a), Term::var(b)]),
+            ],
+        )
+    }
+
+    impl InferenceRule for PartOf {
+        fn name(&self) -> &str {
+            "part-of-transitivity"
+        }
+
+        fn premises(&self) -> Pattern {
+            Pattern::new(vec![part_of("A", "B"), part_of("B", "C")])
+        }
+
+        fn conclusion(&self) -> Term {
+            part_of("A", "C")
+        }
+
+        fn truth_value(&self, space: &AtomSpace, m: &Match) -> TruthValue {
+            let (x, y) = (premise_tv(space, m, 0), premise_tv(space, m, 1));
+            TruthValue::new(x.strength * y.strength, x.confidence.min(y.confidence))
+        }
+    }
+
+    fn assert_part_of(s: &mut AtomSpace, a: &str, b: &str) {
+        let tv = TruthValue::new(0.9, 0.9);
+        let (pred, _) = s.add_node(AtomType::PredicateNode, "part-of", tv);
+        let (a, _) = s.add_node(AtomType::ConceptNode, a, tv);
+        let (b, _) = s.add_node(AtomType::ConceptNode, b, tv);
+        let (list, _) = s.add_link(AtomType::ListLink, vec![a, b], TruthValue::default_tv());
+        s.add_link(AtomType::EvaluationLink, vec![pred, list], tv);
+    }
+
+    #[test]
+    fn builtin_enables_deduction_and_properties() {
+        let r = RuleRegistry::builtin();
+        assert_eq!(
+            r.names(),
+            vec![
+                "deduction",
+                "property-inheritance",
+                "induction",
+                "abduction",
+                "inversion",
+                "parent-similarity",
+                "child-similarity",
+                "inheritance-to-similarity",
+                "similarity-to-inheritance",
+            ]
+        );
+        let enabled: Vec<_> = r.enabled().iter().map(|r| r.name()).collect();
+        assert_eq!(enabled, vec!["deduction", "property-inheritance"]);
+        assert!(r.get("abduction").is_some() && !r.is_enabled("abduction"));
+    }
+
+    #[test]
+    fn enable_and_disable_by_name() {
+        let mut r = RuleRegistry::builtin();
+        assert!(r.set_enabled("abduction", true));
+        assert!(r.set_enabled("deduction", false));
+        assert!(r.set_enabled("property-inheritance", false));
+        assert!(!r.set_enabled("bogus", true));
+        let enabled: Vec<_> = r.enabled().iter().map(|r| r.name()).collect();
+        assert_eq!(enabled, vec!["abduction"]);
+
+        assert_eq!(r.enable_only(&["induction", "bogus"]), Err("bogus".into()));
+        assert!(r.is_enabled("abduction"), "unchanged after an error");
+        r.enable_only(&["induction", "deduction"]).unwrap();
+        let enabled: Vec<_> = r.enabled().iter().map(|r| r.name()).collect();
+        assert_eq!(
+            enabled,
+            vec!["deduction", "induction"],
+            "priority order kept"
+        );
+    }
+
+    #[test]
+    fn register_replaces_by_name() {
+        let mut r = RuleRegistry::new();
+        r.register(Deduction, false);
+        r.register(PartOf, true);
+        r.register(Deduction, true);
+        assert_eq!(r.names(), vec!["deduction", "part-of-transitivity"]);
+        assert!(r.is_enabled("deduction"));
+    }
+
+    #[test]
+    fn external_rule_chains_evaluations() {
+        let mut s = AtomSpace::new();
+        assert_part_of(&mut s, "wheel", "car");
+        assert_part_of(&mut s, "car", "traffic");
+        let mut r = RuleRegistry::new();
+        r.register(PartOf, true);
+
+        let inf = pln::forward_chain_with(&mut s, 2, &r.enabled());
+        assert_eq!(inf.len(), 1);
+        assert_eq!(inf[0].rule, "part-of-transitivity");
+        assert!((inf[0].tv.strength - 0.81).abs() < 1e-9);
+        let wheel = s.find_node(AtomType::ConceptNode, "wheel").unwrap();
+        let traffic = s.find_node(AtomType::ConceptNode, "traffic").unwrap();
+        let list = s.find_link(AtomType::ListLink, &[wheel, traffic]).unwrap();
+        assert_eq!(s.get(inf[0].conclusion_id).unwrap().outgoing[1], list);
+        assert_eq!(
+            s.provenance(inf[0].conclusion_id).unwrap().premises.len(),
+            2
+        );
+    }
+}
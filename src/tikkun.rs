@@ -1,8 +1,13 @@
 //! Tikkun — self-repair diagnostics
-//! Verifies AtomSpace integrity: valid TVs, no orphans, type diversity.
+//! Verifies AtomSpace integrity: valid TVs, no orphans, type diversity,
+//! no links the enabled rules contradict, conserved attention funds.
+
+use std::collections::HashSet;
 
 use crate::atom::AtomType;
-use crate::atomspace::AtomSpace;
+use crate::atomspace::{AtomSpace, LTI_FUNDS, STI_FUNDS};
+use crate::pln;
+use crate::rules::RuleRegistry;
 
 pub struct TikkunCheck {
     pub name: String,
@@ -85,6 +90,46 @@ pub fn run_tikkun(space: &AtomSpace) -> TikkunReport {
         },
     });
 
+    // 6. no stored link contradicted by what the rules derive
+    let registry = RuleRegistry::builtin();
+    let rules = registry.enabled();
+    let contradicted: HashSet<_> = space
+        .all_atoms_sorted()
+        .iter()
+        .filter(|a| a.atom_type.is_link())
+        .flat_map(|a| pln::contradictions(space, a.id, &rules))
+        .map(|c| c.atom)
+        .collect();
+    checks.push(TikkunCheck {
+        name: "no-contradictions".into(),
+        passed: contradicted.is_empty(),
+        detail: if contradicted.is_empty() {
+            None
+        } else {
+            Some(format!("{} contradicted links", contradicted.len()))
+        },
+    });
+
+    // 7. attention funds conserved: bank + atoms = the fixed totals
+    let bank = space.bank();
+    let (sti, lti) = space
+        .all_atoms_sorted()
+        .iter()
+        .fold((bank.sti, bank.lti), |(s, l), a| {
+            (s + a.av.sti, l + a.av.lti)
+        });
+    let (sti_drift, lti_drift) = (sti - STI_FUNDS, lti - LTI_FUNDS);
+    let conserved = sti_drift.abs() < STI_FUNDS * 1e-9 && lti_drift.abs() < LTI_FUNDS * 1e-9;
+    checks.push(TikkunCheck {
+        name: "funds-conserved".into(),
+        passed: conserved,
+        detail: Some(if conserved {
+            format!("bank {:.1} STI, {:.1} LTI", bank.sti, bank.lti)
+        } else {
+            format!("STI off by {:+.3}, LTI off by {:+.3}", sti_drift, lti_drift)
+        }),
+    });
+
     let all_healthy = checks.iter().all(|c| c.passed);
     TikkunReport {
         checks,
@@ -96,8 +141,9 @@ pub fn run_tikkun(space: &AtomSpace) -> TikkunReport {
 mod tests {
     use super::*;
     use crate::atom::*;
-    use crate::atomspace::AtomSpace;
+    use crate::atomspace::{AtomSpace, RemovalPolicy};
     use crate::ontology;
+    use crate::parse;
 
     #[test]
     fn healthy_ontology_passes() {
@@ -105,7 +151,7 @@ mod tests {
         ontology::load_base_ontology(&mut space);
         let report = run_tikkun(&space);
         assert!(report.all_healthy);
-        assert_eq!(report.checks.len(), 5);
+        assert_eq!(report.checks.len(), 7);
     }
 
     #[test]
@@ -179,4 +225,63 @@ mod tests {
             .unwrap();
         assert!(orphans.passed);
     }
+
+    #[test]
+    fn orphaned_links_are_reported() {
+        let mut space = AtomSpace::new();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "cat", TruthValue::new(0.9, 0.8));
+        let (b, _) = space.add_node(AtomType::ConceptNode, "mammal", TruthValue::new(0.9, 0.8));
+        space.add_link(
+            AtomType::InheritanceLink,
+            vec![a, b],
+            TruthValue::new(0.95, 0.9),
+        );
+        space.remove_atom(b, RemovalPolicy::Orphan).unwrap();
+        let report = run_tikkun(&space);
+        let orphans = report
+            .checks
+            .iter()
+            .find(|c| c.name == "no-orphans")
+            .unwrap();
+        assert!(!orphans.passed);
+        assert_eq!(orphans.detail.as_deref(), Some("1 orphan refs"));
+    }
+
+    #[test]
+    fn negation_against_inference_is_reported() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        parse::parse_input(&mut space, "bird is-a flier");
+        assert!(run_tikkun(&space).all_healthy);
+        parse::parse_input(&mut space, "eagle is not a flier");
+        let report = run_tikkun(&space);
+        let check = report
+            .checks
+            .iter()
+            .find(|c| c.name == "no-contradictions")
+            .unwrap();
+        assert!(!check.passed);
+        assert_eq!(check.detail.as_deref(), Some("1 contradicted links"));
+    }
+
+    #[test]
+    fn attention_minted_outside_the_bank_is_reported() {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        let cat = space.find_node(AtomType::ConceptNode, "cat").unwrap();
+        space.set_sti(cat, 30.0);
+        assert!(run_tikkun(&space).all_healthy);
+        space.get_mut(cat).unwrap().av.sti += 5.0;
+        let report = run_tikkun(&space);
+        let check = report
+            .checks
+            .iter()
+            .find(|c| c.name == "funds-conserved")
+            .unwrap();
+        assert!(!check.passed);
+        assert_eq!(
+            check.detail.as_deref(),
+            Some("STI off by +5.000, LTI off by +0.000")
+        );
+    }
 }
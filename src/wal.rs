@@ -0,0 +1,605 @@
+//! WAL — append-only journal of AtomSpace mutations
+//! One JSON object per line. Replaying the journal on top of the snapshot it
+//! was started from reconstructs the space; `compact` folds it into a fresh
+//! snapshot. Entries carry the turn they belong to via `Input` markers, so the
+//! journal doubles as an audit trail of which input produced which atom.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::atom::*;
+use crate::atomspace::{AtomSpace, Provenance, RemovalPolicy};
+use crate::persist::{self, SnapshotError};
+
+/// A single recorded change to the space, in application order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Mutation {
+    /// A new turn began with this input; following entries belong to it
+    Input {
+        turn: u32,
+        text: String,
+    },
+    AddNode {
+        id: AtomId,
+        #[serde(rename = "type")]
+        atom_type: AtomType,
+        name: String,
+        tv: TruthValue,
+    },
+    AddLink {
+        id: AtomId,
+        #[serde(rename = "type")]
+        atom_type: AtomType,
+        outgoing: Vec<AtomId>,
+        tv: TruthValue,
+    },
+    SetTv {
+        id: AtomId,
+        tv: TruthValue,
+    },
+    SetSti {
+        id: AtomId,
+        sti: f64,
+    },
+    SetLti {
+        id: AtomId,
+        lti: f64,
+    },
+    /// Provenance of an inferred atom
+    Derive {
+        id: AtomId,
+        rule: String,
+        premises: Vec<AtomId>,
+        tv: TruthValue,
+    },
+    /// A derived atom was asserted outright; its provenance no longer applies
+    Assert {
+        id: AtomId,
+    },
+    Remove {
+        id: AtomId,
+    },
+}
+
+#[derive(Debug)]
+pub enum WalError {
+    Io(io::Error),
+    Corrupt {
+        line: usize,
+        msg: String,
+    },
+    Diverged {
+        line: usize,
+        expected: AtomId,
+        got: AtomId,
+    },
+    Snapshot(SnapshotError),
+}
+
+impl fmt::Display for WalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalError::Io(e) => write!(f, "journal I/O error: {}", e),
+            WalError::Corrupt { line, msg } => write!(f, "journal line {}: {}", line, msg),
+            WalError::Diverged {
+                line,
+                expected,
+                got,
+            } => write!(
+                f,
+                "journal line {}: expected atom id {}, space assigned {}",
+                line, expected, got
+            ),
+            WalError::Snapshot(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for WalError {}
+
+impl From<io::Error> for WalError {
+    fn from(e: io::Error) -> Self {
+        WalError::Io(e)
+    }
+}
+
+impl From<SnapshotError> for WalError {
+    fn from(e: SnapshotError) -> Self {
+        WalError::Snapshot(e)
+    }
+}
+
+/// Outcome of a replay: entries applied vs. skipped as already present
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub applied: usize,
+    pub skipped: usize,
+    /// Bytes of a torn last record cut from the end of the journal
+    pub truncated: u64,
+}
+
+/// Apply one mutation. Every entry is idempotent against a space that already
+/// contains it, so replaying a journal whose compaction was interrupted after
+/// the snapshot was written (but before truncation) is harmless.
+/// Returns false when the entry was skipped. Errors carry line 0; `replay`
+/// fills in the journal line.
+pub fn apply(space: &mut AtomSpace, m: &Mutation) -> Result<bool, WalError> {
+    match m {
+        Mutation::Input { turn, .. } => {
+            space.turn = *turn;
+            Ok(true)
+        }
+        Mutation::AddNode {
+            id,
+            atom_type,
+            name,
+            tv,
+        } => {
+            if *id < space.next_id() {
+                return Ok(false);
+            }
+            let (got, _) = space.add_node(*atom_type, name, *tv);
+            if got == *id {
+                Ok(true)
+            } else {
+                Err(WalError::Diverged {
+                    line: 0,
+                    expected: *id,
+                    got,
+                })
+            }
+        }
+        Mutation::AddLink {
+            id,
+            atom_type,
+            outgoing,
+            tv,
+        } => {
+            if *id < space.next_id() {
+                return Ok(false);
+            }
+            // A hand-edited journal may hold a link its type doesn't allow
+            let (got, _) = space
+                .try_add_link(*atom_type, outgoing.clone(), *tv)
+                .map_err(|e| WalError::Corrupt {
+                    line: 0,
+                    msg: e.to_string(),
+                })?;
+            if got == *id {
+                Ok(true)
+            } else {
+                Err(WalError::Diverged {
+                    line: 0,
+                    expected: *id,
+                    got,
+                })
+            }
+        }
+        Mutation::SetTv { id, tv } => {
+            if space.get(*id).is_none() {
+                return Ok(false);
+            }
+            space.set_tv(*id, *tv);
+            Ok(true)
+        }
+        Mutation::SetSti { id, sti } => {
+            if space.get(*id).is_none() {
+                return Ok(false);
+            }
+            space.set_sti(*id, *sti);
+            Ok(true)
+        }
+        Mutation::SetLti { id, lti } => {
+            if space.get(*id).is_none() {
+                return Ok(false);
+            }
+            space.set_lti(*id, *lti);
+            Ok(true)
+        }
+        Mutation::Derive {
+            id,
+            rule,
+            premises,
+            tv,
+        } => {
+            if space.get(*id).is_none() {
+                return Ok(false);
+            }
+            space.set_provenance(
+                *id,
+                Provenance {
+                    rule: rule.clone(),
+                    premises: premises.clone(),
+                    tv: *tv,
+                },
+            );
+            Ok(true)
+        }
+        Mutation::Assert { id } => {
+            if space.get(*id).is_none() {
+                return Ok(false);
+            }
+            space.clear_provenance(*id);
+            Ok(true)
+        }
+        // Cascades are journaled atom by atom, so each removal is single
+        Mutation::Remove { id } => Ok(space.remove_atom(*id, RemovalPolicy::Orphan).is_ok()),
+    }
+}
+
+/// Read every entry from a journal file, stopping before a torn last record
+pub fn read_entries(path: &Path) -> Result<Vec<Mutation>, WalError> {
+    read_journal(path).map(|(entries, _)| entries)
+}
+
+/// Entries, and the length of the intact prefix when the last record is
+/// torn: unterminated and unparsable, as a crash mid-append leaves it.
+/// Anything unparsable before the last line is corruption.
+fn read_journal(path: &Path) -> Result<(Vec<Mutation>, Option<u64>), WalError> {
+    let bytes = std::fs::read(path)?;
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut line = 0;
+    while start < bytes.len() {
+        line += 1;
+        let (end, terminated) = match bytes[start..].iter().position(|&b| b == b'\n') {
+            Some(n) => (start + n, true),
+            None => (bytes.len(), false),
+        };
+        let text = &bytes[start..end];
+        if !text.iter().all(u8::is_ascii_whitespace) {
+            match serde_json::from_slice(text) {
+                Ok(m) => entries.push(m),
+                Err(_) if !terminated => return Ok((entries, Some(start as u64))),
+                Err(e) => {
+                    return Err(WalError::Corrupt {
+                        line,
+                        msg: e.to_string(),
+                    })
+                }
+            }
+        }
+        start = end + 1;
+    }
+    Ok((entries, None))
+}
+
+/// Replay a journal file on top of `space`. A torn last record is cut off
+/// the file and counted in `ReplayReport::truncated`.
+pub fn replay(space: &mut AtomSpace, path: &Path) -> Result<ReplayReport, WalError> {
+    let mut report = ReplayReport::default();
+    let (entries, torn) = read_journal(path)?;
+    if let Some(len) = torn {
+        let file = OpenOptions::new().write(true).open(path)?;
+        report.truncated = file.metadata()?.len() - len;
+        file.set_len(len)?;
+        file.sync_all()?;
+    }
+    for (i, m) in entries.iter().enumerate() {
+        match apply(space, m) {
+            Ok(true) => report.applied += 1,
+            Ok(false) => report.skipped += 1,
+            Err(WalError::Corrupt { msg, .. }) => {
+                return Err(WalError::Corrupt { line: i + 1, msg })
+            }
+            Err(WalError::Diverged { expected, got, .. }) => {
+                return Err(WalError::Diverged {
+                    line: i + 1,
+                    expected,
+                    got,
+                })
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(report)
+}
+
+/// Append-only journal file
+pub struct Wal {
+    path: PathBuf,
+    file: File,
+}
+
+impl Wal {
+    pub fn open(path: &Path) -> Result<Self, WalError> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        // A last record missing its newline would run into the next append
+        let len = file.metadata()?.len();
+        if len > 0 {
+            let mut last = [0u8];
+            file.seek(SeekFrom::Start(len - 1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n")?;
+                file.sync_data()?;
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append entries and sync them to disk before returning. On failure
+    /// the file is cut back to where it was, so the caller can retry them.
+    pub fn append(&mut self, entries: &[Mutation]) -> Result<(), WalError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut buf = Vec::new();
+        for m in entries {
+            serde_json::to_writer(&mut buf, m).expect("mutation serializes");
+            buf.push(b'\n');
+        }
+        let len = self.file.metadata()?.len();
+        if let Err(e) = self
+            .file
+            .write_all(&buf)
+            .and_then(|_| self.file.sync_data())
+        {
+            // Don't leave a partial record for the next append to follow
+            let _ = self.file.set_len(len);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Fold the journal into a snapshot of `space` and truncate it.
+    /// The snapshot is written and synced first, so a crash in between only
+    /// leaves entries that replay as no-ops, never an empty journal next to
+    /// a snapshot that did not reach the disk.
+    pub fn compact(&mut self, space: &AtomSpace, snapshot: &Path) -> Result<(), WalError> {
+        persist::save_snapshot(space, snapshot)?;
+        self.file.set_len(0)?;
+        self.file.sync_all()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cogloop;
+    use crate::ecan::EcanConfig;
+    use crate::ontology;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("coggy-wal-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn base() -> AtomSpace {
+        let mut space = AtomSpace::new();
+        ontology::load_base_ontology(&mut space);
+        space
+    }
+
+    fn assert_same(a: &AtomSpace, b: &AtomSpace) {
+        assert_eq!(a.size(), b.size());
+        assert_eq!(a.turn, b.turn);
+        assert_eq!(a.next_id(), b.next_id());
+        assert_eq!(a.bank(), b.bank());
+        for atom in a.all_atoms_sorted() {
+            let other = b.get(atom.id).expect("atom present after replay");
+            assert_eq!(other.name, atom.name);
+            assert_eq!(other.outgoing, atom.outgoing);
+            assert_eq!(other.tv, atom.tv);
+            assert_eq!(other.av.sti, atom.av.sti);
+            assert_eq!(other.av.lti, atom.av.lti);
+            assert_eq!(b.provenance(atom.id), a.provenance(atom.id));
+        }
+    }
+
+    #[test]
+    fn journal_records_mutations_in_order() {
+        let mut space = AtomSpace::new();
+        space.enable_journal();
+        let (a, _) = space.add_node(AtomType::ConceptNode, "cat", TruthValue::new(0.9, 0.5));
+        space.add_node(AtomType::ConceptNode, "cat", TruthValue::new(0.8, 0.9));
+        space.set_sti(a, 3.0);
+        let entries = space.take_journal();
+        assert_eq!(entries.len(), 3);
+        assert!(matches!(entries[0], Mutation::AddNode { id, .. } if id == a));
+        assert!(matches!(entries[1], Mutation::SetTv { id, .. } if id == a));
+        assert_eq!(entries[2], Mutation::SetSti { id: a, sti: 3.0 });
+        assert!(space.take_journal().is_empty());
+
+        // Entries handed back after a failed append go ahead of newer ones
+        space.set_sti(a, 4.0);
+        space.restore_journal(entries.clone());
+        let retried = space.take_journal();
+        assert_eq!(retried[..3], entries[..]);
+        assert_eq!(retried[3], Mutation::SetSti { id: a, sti: 4.0 });
+    }
+
+    #[test]
+    fn journal_disabled_by_default() {
+        let mut space = AtomSpace::new();
+        space.add_node(AtomType::ConceptNode, "cat", TruthValue::new(0.9, 0.5));
+        assert!(space.take_journal().is_empty());
+    }
+
+    #[test]
+    fn replay_reconstructs_cogloop_state() {
+        let dir = temp_dir("replay");
+        let path = dir.join("space.wal");
+        let config = EcanConfig::default();
+
+        let mut live = base();
+        live.enable_journal();
+        let mut wal = Wal::open(&path).unwrap();
+        for input in ["cat is-a pet", "cat likes fish", "penguin is-a bird"] {
+            cogloop::run(&mut live, input, &config);
+            wal.append(&live.take_journal()).unwrap();
+        }
+        let cat = live.find_node(AtomType::ConceptNode, "cat").unwrap();
+        live.remove_atom(cat, RemovalPolicy::Cascade).unwrap();
+        wal.append(&live.take_journal()).unwrap();
+
+        let mut recovered = base();
+        let report = replay(&mut recovered, &path).unwrap();
+        assert_eq!(report.skipped, 0);
+        assert_same(&live, &recovered);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn entries_audit_which_input_created_atoms() {
+        let mut space = base();
+        space.enable_journal();
+        cogloop::run(&mut space, "cat is-a pet", &EcanConfig::default());
+        let entries = space.take_journal();
+        assert_eq!(
+            entries[0],
+            Mutation::Input {
+                turn: 1,
+                text: "cat is-a pet".into()
+            }
+        );
+        let pet = space.find_node(AtomType::ConceptNode, "pet").unwrap();
+        assert!(entries
+            .iter()
+            .any(|m| matches!(m, Mutation::AddNode { id, .. } if *id == pet)));
+    }
+
+    #[test]
+    fn compact_then_stale_replay_is_noop() {
+        let dir = temp_dir("compact");
+        let wal_path = dir.join("space.wal");
+        let snap_path = dir.join("space.json");
+
+        let mut live = base();
+        live.enable_journal();
+        let mut wal = Wal::open(&wal_path).unwrap();
+        cogloop::run(&mut live, "cat is-a pet", &EcanConfig::default());
+        let entries = live.take_journal();
+        wal.append(&entries).unwrap();
+
+        // Simulate a crash after the snapshot but before truncation
+        persist::save_snapshot(&live, &snap_path).unwrap();
+        let mut restored = persist::load_snapshot(&snap_path).unwrap();
+        let report = replay(&mut restored, &wal_path).unwrap();
+        assert!(report.skipped > 0);
+        assert_same(&live, &restored);
+
+        wal.compact(&live, &snap_path).unwrap();
+        assert!(read_entries(&wal_path).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_line_is_reported() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join("space.wal");
+        std::fs::write(
+            &path,
+            "{\"op\":\"input\",\"turn\":1,\"text\":\"x\"}\nnot json\n",
+        )
+        .unwrap();
+        let mut space = AtomSpace::new();
+        assert!(matches!(
+            replay(&mut space, &path),
+            Err(WalError::Corrupt { line: 2, .. })
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ill_typed_link_is_reported() {
+        let dir = temp_dir("ill-typed");
+        let path = dir.join("space.wal");
+        let entries = [
+            Mutation::AddNode {
+                id: 1,
+                atom_type: AtomType::ConceptNode,
+                name: "cat".into(),
+                tv: TruthValue::new(0.9, 0.8),
+            },
+            Mutation::AddLink {
+                id: 2,
+                atom_type: AtomType::NotLink,
+                outgoing: vec![1, 1],
+                tv: TruthValue::new(0.9, 0.8),
+            },
+        ];
+        let text: Vec<String> = entries
+            .iter()
+            .map(|m| serde_json::to_string(m).unwrap())
+            .collect();
+        std::fs::write(&path, text.join("\n")).unwrap();
+        let mut space = AtomSpace::new();
+        assert!(matches!(
+            replay(&mut space, &path),
+            Err(WalError::Corrupt { line: 2, .. })
+        ));
+        assert_eq!(space.size(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn torn_last_record_is_truncated() {
+        let dir = temp_dir("torn");
+        let path = dir.join("space.wal");
+        let input = "{\"op\":\"input\",\"turn\":3,\"text\":\"x\"}\n";
+        std::fs::write(&path, format!("{}{{\"op\":\"add_no", input)).unwrap();
+        let mut space = AtomSpace::new();
+        let report = replay(&mut space, &path).unwrap();
+        assert_eq!(report.applied, 1);
+        assert_eq!(report.truncated, 13);
+        assert_eq!(space.turn, 3);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), input);
+
+        // Appends after the cut start on a line of their own
+        let mut wal = Wal::open(&path).unwrap();
+        wal.append(&[Mutation::Input {
+            turn: 4,
+            text: "y".into(),
+        }])
+        .unwrap();
+        assert_eq!(read_entries(&path).unwrap().len(), 2);
+
+        // A torn line anywhere but last is still corruption
+        std::fs::write(&path, format!("{{\"op\":\"add_no\n{}", input)).unwrap();
+        assert!(matches!(
+            replay(&mut AtomSpace::new(), &path),
+            Err(WalError::Corrupt { line: 1, .. })
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn apply_rejects_ill_typed_links_without_panicking() {
+        let mut space = AtomSpace::new();
+        let (cat, _) = space.add_node(AtomType::ConceptNode, "cat", TruthValue::new(0.9, 0.8));
+        let m = Mutation::AddLink {
+            id: space.next_id(),
+            atom_type: AtomType::EvaluationLink,
+            outgoing: vec![cat, cat],
+            tv: TruthValue::new(0.9, 0.8),
+        };
+        assert!(matches!(
+            apply(&mut space, &m),
+            Err(WalError::Corrupt { line: 0, .. })
+        ));
+        assert_eq!(space.size(), 1);
+    }
+}